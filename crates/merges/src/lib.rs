@@ -1,3 +1,5 @@
+use std::{collections::HashMap, hash::Hash};
+
 pub trait Merges: Sized + Clone {
     fn merge(&self, rhs: &Self) -> Self;
 
@@ -25,3 +27,41 @@ pub fn nested_merge<T: Merges>(lhs: &Option<T>, rhs: &Option<T>) -> Option<T> {
 pub fn primitive_merge<T: Clone>(lhs: &Option<T>, rhs: &Option<T>) -> Option<T> {
     rhs.as_ref().or(lhs.as_ref()).cloned()
 }
+
+/// Concatenates both sides, so a list set on the right hand side is added to
+/// the end of the left's list instead of replacing it.
+pub fn append_merge<T: Clone>(lhs: &Option<Vec<T>>, rhs: &Option<Vec<T>>) -> Option<Vec<T>> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs.iter().chain(rhs).cloned().collect()),
+        _ => primitive_merge(lhs, rhs),
+    }
+}
+
+/// Merges maps key by key. Keys that only exist on one side are kept as-is
+/// and values for keys on both sides are merged with their `Merges` impl.
+pub fn deep_merge<K, V>(
+    lhs: &Option<HashMap<K, V>>,
+    rhs: &Option<HashMap<K, V>>,
+) -> Option<HashMap<K, V>>
+where
+    K: Eq + Hash + Clone,
+    V: Merges,
+{
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => {
+            let mut merged = lhs.clone();
+
+            for (key, rhs_value) in rhs {
+                let value = match lhs.get(key) {
+                    Some(lhs_value) => lhs_value.merge(rhs_value),
+                    None => rhs_value.clone(),
+                };
+
+                merged.insert(key.clone(), value);
+            }
+
+            Some(merged)
+        }
+        _ => primitive_merge(lhs, rhs),
+    }
+}
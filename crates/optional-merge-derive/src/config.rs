@@ -1,3 +1,4 @@
 pub const FIELD_ATTR: &str = "mergeable";
 pub const MERGEABLE_NAME: &str = "Mergeable";
 pub const UNMERGEABLE_NAME: &str = "Unmergeable";
+pub const UNMERGEABLE_TS_PREFIX: &str = "Resolved";
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 
 use darling::{FromField, FromMeta};
-use syn::{Data, DeriveInput, Field, Fields, Type};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, Ident, Type, TypePath};
 
-use crate::config::FIELD_ATTR;
+use crate::{config::FIELD_ATTR, errors::Errors, strategy::MergeStrategy};
 
 #[allow(dead_code)]
 #[derive(Debug, FromField)]
@@ -13,22 +15,50 @@ pub struct MergeableField {
     ty: Type,
     #[darling(default)]
     nested: bool,
+    #[darling(default)]
+    inherit: bool,
+    #[darling(default)]
+    strategy: Option<MergeStrategy>,
+    #[darling(default)]
+    with: Option<syn::Path>,
 }
 
+/// Options set on the item itself, i.e. `#[mergeable(rename = "...")]`.
 #[derive(Clone, FromMeta, Debug, Default)]
 #[darling(default)]
-pub struct FieldOptions {
+pub struct GlobalOptions {
+    /// The name the generated types are exported to TypeScript as, instead
+    /// of the name of the item.
     pub rename: Option<String>,
     pub use_null_in_serde: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FieldOptions {
     pub is_nested: bool,
+    pub is_inherited: bool,
+    pub strategy: Option<MergeStrategy>,
 }
 
 impl From<MergeableField> for FieldOptions {
     fn from(field: MergeableField) -> Self {
         Self {
-            rename: None,
-            use_null_in_serde: false,
             is_nested: field.nested,
+            is_inherited: field.inherit,
+            strategy: field
+                .strategy
+                .or_else(|| field.with.map(MergeStrategy::Function)),
+        }
+    }
+}
+
+impl FieldOptions {
+    /// The function used to merge this field, i.e. `merges::primitive_merge`.
+    pub fn merge_fn(&self) -> TokenStream {
+        if self.is_nested {
+            quote! { merges::nested_merge }
+        } else {
+            self.strategy.clone().unwrap_or_default().merge_fn()
         }
     }
 }
@@ -41,31 +71,39 @@ impl FieldsOptions {
         Self(HashMap::new())
     }
 
-    pub fn insert_by_field(&mut self, field: &Field) {
-        let mergeable_field = MergeableField::from_field(field).unwrap();
-
-        self.insert(
-            field
-                .ident
-                .clone()
-                .expect("Expected named field to have ident")
-                .to_string(),
-            mergeable_field.into(),
-        );
+    pub fn insert_by_field(
+        &mut self,
+        variant: Option<&Ident>,
+        index: usize,
+        field: &Field,
+    ) -> syn::Result<FieldOptions> {
+        let mergeable_field = MergeableField::from_field(field)?;
+
+        if let (Some(_), Some(with)) = (&mergeable_field.strategy, &mergeable_field.with) {
+            return Err(syn::Error::new_spanned(
+                with,
+                "a field is merged either by a `strategy` or `with` a function, not both",
+            ));
+        }
+
+        let field_options: FieldOptions = mergeable_field.into();
+
+        self.insert(field_key(variant, index, field), field_options.clone());
+
+        Ok(field_options)
     }
 
     pub fn insert(&mut self, field_name: String, options: FieldOptions) {
         self.0.insert(field_name, options);
     }
 
-    pub fn get_by_field(&self, field: &Field) -> FieldOptions {
-        let field_name = field
-            .ident
-            .clone()
-            .expect("Expected named field to have name")
-            .to_string();
-
-        self.get(&field_name)
+    pub fn get_by_field(
+        &self,
+        variant: Option<&Ident>,
+        index: usize,
+        field: &Field,
+    ) -> FieldOptions {
+        self.get(&field_key(variant, index, field))
     }
 
     pub fn get(&self, field_name: &str) -> FieldOptions {
@@ -73,29 +111,115 @@ impl FieldsOptions {
     }
 }
 
-pub fn extract_field_attrs(ast: &mut DeriveInput) -> FieldsOptions {
-    let mut field_options = FieldsOptions::new();
+/// Fields are keyed by name, or by position for tuple fields. Enum fields are
+/// additionally prefixed by their variant since field names only need to be
+/// unique within a variant.
+pub fn field_key(variant: Option<&Ident>, index: usize, field: &Field) -> String {
+    let member = field
+        .ident
+        .as_ref()
+        .map_or_else(|| index.to_string(), |ident| ident.to_string());
+
+    match variant {
+        Some(variant) => format!("{}::{}", variant, member),
+        None => member,
+    }
+}
 
-    if let Data::Struct(unmergeable_struct) = &mut ast.data {
-        if let Fields::Named(named_fields) = &mut unmergeable_struct.fields {
-            for field in named_fields.named.iter_mut() {
-                let mergeable_attr_index = field
-                    .attrs
-                    .iter()
-                    .position(|attr| attr.path.is_ident(FIELD_ATTR));
+/// Every set of fields in the item along with the enum variant it belongs to,
+/// if any.
+pub fn fields_mut(data: &mut Data) -> syn::Result<Vec<(Option<Ident>, &mut Fields)>> {
+    match data {
+        Data::Struct(mergeable_struct) => Ok(vec![(None, &mut mergeable_struct.fields)]),
+        Data::Enum(mergeable_enum) => Ok(mergeable_enum
+            .variants
+            .iter_mut()
+            .map(|variant| (Some(variant.ident.clone()), &mut variant.fields))
+            .collect()),
+        Data::Union(mergeable_union) => Err(syn::Error::new_spanned(
+            mergeable_union.union_token,
+            "#[mergeable] does not support unions",
+        )),
+    }
+}
+
+/// Struct variants are merged field by field, so their fields become
+/// optional just like a struct's would. Tuple and unit variants are always
+/// replaced as a whole.
+pub fn is_mergeable_variant(variant: Option<&Ident>, fields: &Fields) -> bool {
+    variant.is_none() || matches!(fields, Fields::Named(_))
+}
+
+fn validate_field(
+    variant: Option<&Ident>,
+    fields: &Fields,
+    field: &Field,
+    field_options: &FieldOptions,
+) -> syn::Result<()> {
+    if field_options.is_inherited && variant.is_some() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`inherit` is only supported on struct fields",
+        ));
+    }
+
+    if field_options.is_nested {
+        if field_options.strategy.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`nested` fields are always merged field by field and can't set a `strategy`",
+            ));
+        }
+
+        if !is_mergeable_variant(variant, fields) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`nested` is only supported on fields of structs and struct variants",
+            ));
+        }
 
-                if let Some(index) = mergeable_attr_index {
-                    field_options.insert_by_field(field);
+        if !matches!(field.ty, Type::Path(TypePath { qself: None, .. })) {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`nested` fields must be a #[mergeable] type or a type parameter",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses and strips the `#[mergeable(...)]` attribute from every field,
+/// reporting all invalid options at once.
+pub fn extract_field_attrs(ast: &mut DeriveInput) -> syn::Result<FieldsOptions> {
+    let mut field_options = FieldsOptions::new();
+    let mut errors = Errors::default();
+
+    for (variant, fields) in fields_mut(&mut ast.data)? {
+        let fields_shape = fields.clone();
+
+        for (index, field) in fields.iter_mut().enumerate() {
+            if !field
+                .attrs
+                .iter()
+                .any(|attr| attr.path.is_ident(FIELD_ATTR))
+            {
+                continue;
+            }
+
+            let result = field_options
+                .insert_by_field(variant.as_ref(), index, field)
+                .and_then(|options| {
+                    validate_field(variant.as_ref(), &fields_shape, field, &options)
+                });
 
-                    field.attrs.remove(index);
-                }
+            if let Err(error) = result {
+                errors.push(error);
             }
-        } else {
-            unimplemented!();
+
+            field.attrs.retain(|attr| !attr.path.is_ident(FIELD_ATTR));
         }
-    } else {
-        unimplemented!();
     }
 
-    field_options
+    errors.finish(field_options)
 }
@@ -1,185 +1,568 @@
 extern crate proc_macro;
 
 mod config;
+mod errors;
 mod field_options;
+mod strategy;
 
-use config::{MERGEABLE_NAME, UNMERGEABLE_NAME};
+use config::{MERGEABLE_NAME, UNMERGEABLE_NAME, UNMERGEABLE_TS_PREFIX};
 use darling::FromMeta;
-use field_options::{extract_field_attrs, FieldOptions, FieldsOptions};
-use proc_macro2::Span;
-use quote::{quote, ToTokens};
-use std::str::FromStr;
+use errors::Errors;
+use field_options::{
+    extract_field_attrs, fields_mut, is_mergeable_variant, FieldsOptions, GlobalOptions,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
 use syn::{
-    self, parse_macro_input, parse_quote, Attribute, AttributeArgs, Data, DeriveInput, Fields,
-    LitStr, Type,
+    self, parse_macro_input, parse_quote, Attribute, AttributeArgs, Data, DataEnum, DataStruct,
+    DeriveInput, Field, Generics, Ident, Index, LitStr, Member, Meta, NestedMeta, Path,
+    PathArguments, PathSegment, Type, TypePath,
 };
 
 fn build_skip_optional_attr() -> Attribute {
     parse_quote! { #[serde(skip_serializing_if = "Option::is_none")] }
 }
 
+fn build_ts_optional_attr() -> Attribute {
+    parse_quote! { #[ts(optional)] }
+}
+
+fn field_member(index: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(index)),
+    }
+}
+
+fn is_type_param(ty: &Type, generics: &Generics) -> bool {
+    if let Type::Path(TypePath { qself: None, path }) = ty {
+        if let Some(ident) = path.get_ident() {
+            return generics.type_params().any(|param| &param.ident == ident);
+        }
+    }
+
+    false
+}
+
+/// The type a nested field has in the generated `form` (either `Mergeable`
+/// or `Unmergeable`), i.e. `FontStyles` becomes `FontStyles::Mergeable` and
+/// `Edges<Pt>` becomes `Edges::Mergeable<Pt>`.
+///
+/// Type parameters of the item itself are expected to implement `Merges`
+/// already so they are used as-is. Any other shape of type has already been
+/// rejected by `extract_field_attrs`.
+fn nested_type(ty: &Type, form: &str, generics: &Generics) -> Type {
+    if is_type_param(ty, generics) {
+        return ty.clone();
+    }
+
+    if let Type::Path(TypePath { qself: None, path }) = ty {
+        let mut path = path.clone();
+        let last_segment = path
+            .segments
+            .last_mut()
+            .expect("Type paths have at least one segment");
+        let arguments = std::mem::replace(&mut last_segment.arguments, PathArguments::None);
+
+        path.segments.push(PathSegment {
+            ident: Ident::new(form, Span::call_site()),
+            arguments,
+        });
+
+        Type::Path(TypePath { qself: None, path })
+    } else {
+        unreachable!("Nested fields are validated to be type paths")
+    }
+}
+
+/// Generics for the impls that merge or clone the `Mergeable` form. Like
+/// serde's derives, the bounds these need are added here so type parameters
+/// don't have to be bounded on the item itself: every type parameter must be
+/// `Clone`, and those used as nested fields must also implement `Merges`.
+fn with_merge_bounds<'a>(generics: &Generics, fields: impl Iterator<Item = &'a Field>) -> Generics {
+    let mut generics = generics.clone();
+
+    let nested_params = fields
+        .map(|field| &field.ty)
+        .filter(|ty| is_type_param(ty, &generics))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let type_params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+
+    let where_clause = generics.make_where_clause();
+
+    for param in type_params {
+        where_clause.predicates.push(parse_quote! { #param: Clone });
+    }
+
+    for param in nested_params {
+        where_clause
+            .predicates
+            .push(parse_quote! { #param: merges::Merges });
+    }
+
+    generics
+}
+
 fn convert_fields_to_optional(
     ast: &mut DeriveInput,
     fields_options: &FieldsOptions,
-    global_options: &FieldOptions,
-) {
-    if let Data::Struct(mergeable_struct) = &mut ast.data {
-        if let Fields::Named(named_fields) = &mut mergeable_struct.fields {
-            named_fields.named.iter_mut().for_each(|field| {
-                let field_options = fields_options.get_by_field(field);
-
-                let mergeable_type = if field_options.is_nested {
-                    let ty_name = format!("{}::{}", field.ty.to_token_stream(), MERGEABLE_NAME);
-                    Type::Verbatim(proc_macro2::TokenStream::from_str(&ty_name).unwrap())
-                } else {
-                    field.ty.clone()
-                };
+    global_options: &GlobalOptions,
+) -> syn::Result<()> {
+    let generics = ast.generics.clone();
+    let is_struct = matches!(ast.data, Data::Struct(_));
+
+    for (variant, fields) in fields_mut(&mut ast.data)? {
+        if !is_mergeable_variant(variant.as_ref(), fields) {
+            continue;
+        }
 
+        for (index, field) in fields.iter_mut().enumerate() {
+            let field_options = fields_options.get_by_field(variant.as_ref(), index, field);
+
+            let mergeable_type = if field_options.is_nested {
+                nested_type(&field.ty, MERGEABLE_NAME, &generics)
+            } else {
+                field.ty.clone()
+            };
+
+            if is_struct {
                 field.vis = parse_quote! { pub };
-                if !global_options.use_null_in_serde {
-                    field.attrs.push(build_skip_optional_attr());
-                }
-                field.ty = parse_quote! { Option< #mergeable_type > };
-            });
+            }
+            if !global_options.use_null_in_serde {
+                field.attrs.push(build_skip_optional_attr());
+            }
+            // TypeScript has no optional tuple elements
+            if field.ident.is_some() {
+                field.attrs.push(build_ts_optional_attr());
+            }
+            field.ty = parse_quote! { Option< #mergeable_type > };
         }
-    } else {
-        unimplemented!()
     }
+
+    Ok(())
 }
 
 fn convert_nested_fields(
     ast: &mut DeriveInput,
     fields_options: &FieldsOptions,
-    _global_options: &FieldOptions,
-) {
-    if let Data::Struct(mergeable_struct) = &mut ast.data {
-        if let Fields::Named(named_fields) = &mut mergeable_struct.fields {
-            named_fields.named.iter_mut().for_each(|field| {
-                let field_options = fields_options.get_by_field(field);
-
-                let mergeable_type = if field_options.is_nested {
-                    let ty_name = format!("{}::{}", field.ty.to_token_stream(), UNMERGEABLE_NAME);
-                    Type::Verbatim(proc_macro2::TokenStream::from_str(&ty_name).unwrap())
-                } else {
-                    field.ty.clone()
-                };
+    _global_options: &GlobalOptions,
+) -> syn::Result<()> {
+    let generics = ast.generics.clone();
+    let is_struct = matches!(ast.data, Data::Struct(_));
 
+    for (variant, fields) in fields_mut(&mut ast.data)? {
+        for (index, field) in fields.iter_mut().enumerate() {
+            let field_options = fields_options.get_by_field(variant.as_ref(), index, field);
+
+            if field_options.is_nested {
+                field.ty = nested_type(&field.ty, UNMERGEABLE_NAME, &generics);
+            }
+
+            if is_struct {
                 field.vis = parse_quote! { pub };
-                field.ty = parse_quote! { #mergeable_type };
-            });
+            }
         }
-    } else {
-        unimplemented!()
     }
+
+    Ok(())
 }
 
-#[proc_macro_attribute]
-pub fn mergeable(
-    attr: proc_macro::TokenStream,
-    input: proc_macro::TokenStream,
-) -> proc_macro::TokenStream {
-    let mut original_ast: DeriveInput = syn::parse(input).unwrap();
+fn expand_struct(
+    original_struct: &DataStruct,
+    field_options: &FieldsOptions,
+    generics: &Generics,
+) -> TokenStream {
+    let mergeable_name = Ident::new(MERGEABLE_NAME, Span::call_site());
+    let unmergeable_name = Ident::new(UNMERGEABLE_NAME, Span::call_site());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let fields = original_struct
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            (
+                field_member(index, field),
+                field_options.get_by_field(None, index, field),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let merge_fields = fields.iter().map(|(name, field_options)| {
+        let merge_fn = field_options.merge_fn();
+
+        quote! {
+            #name: #merge_fn(&self.#name, &rhs.#name)
+        }
+    });
 
-    let attr_args = parse_macro_input!(attr as AttributeArgs);
-    let global_options =
-        FieldOptions::from_list(&attr_args).expect("Global options could not parse attr list");
+    let to_mergeable = fields.iter().map(|(name, field_options)| {
+        if field_options.is_nested {
+            quote! {
+                #name: Some(orig.#name.into())
+            }
+        } else {
+            quote! {
+                #name: Some(orig.#name)
+            }
+        }
+    });
 
-    let field_options = extract_field_attrs(&mut original_ast);
+    let to_unwrapped = fields.iter().map(|(name, field_options)| {
+        if field_options.is_nested {
+            quote! {
+                #name: orig.#name.unwrap().into()
+            }
+        } else {
+            quote! {
+                #name: orig.#name.unwrap()
+            }
+        }
+    });
 
-    let mut mergeable_ast = original_ast.clone();
-    let original_name = original_ast.ident.clone();
+    let inherit_fields = fields
+        .iter()
+        .filter(|(_, field_options)| field_options.is_inherited)
+        .map(|(name, field_options)| {
+            let merge_fn = field_options.merge_fn();
 
-    let mergeable_name = syn::Ident::new(MERGEABLE_NAME, Span::call_site());
-    let unmergeable_name = syn::Ident::new(UNMERGEABLE_NAME, Span::call_site());
+            quote! {
+                #name: #merge_fn(&parent.#name, &self.#name)
+            }
+        });
+
+    let default_fields = fields.iter().map(|(name, _)| quote! { #name: None });
+
+    let merge_generics = with_merge_bounds(
+        generics,
+        original_struct
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(index, field)| field_options.get_by_field(None, *index, field).is_nested)
+            .map(|(_, field)| field),
+    );
+    let (merge_impl_generics, _, merge_where_clause) = merge_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics From<#unmergeable_name #ty_generics> for #mergeable_name #ty_generics #where_clause {
+            fn from(orig: #unmergeable_name #ty_generics) -> Self {
+                Self {
+                    #(#to_mergeable),*
+                }
+            }
+        }
 
-    mergeable_ast.ident = mergeable_name.clone();
-    original_ast.ident = unmergeable_name.clone();
+        impl #impl_generics From<#mergeable_name #ty_generics> for #unmergeable_name #ty_generics #where_clause {
+            fn from(orig: #mergeable_name #ty_generics) -> Self {
+                Self {
+                    #(#to_unwrapped),*
+                }
+            }
+        }
 
-    convert_fields_to_optional(&mut mergeable_ast, &field_options, &global_options);
-    convert_nested_fields(&mut original_ast, &field_options, &global_options);
+        // Implemented by hand so generic structs don't need `Default` type
+        // parameters for an all-`None` default
+        impl #impl_generics Default for #mergeable_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#default_fields),*
+                }
+            }
+        }
 
-    let merge_fields = if let Data::Struct(s) = &original_ast.data {
-        if let Fields::Named(named_fields) = &s.fields {
-            named_fields.named.iter().map(|field| {
-                let field_options = field_options.get_by_field(field);
-                let name = field.clone().ident.unwrap();
+        impl #merge_impl_generics Merges for #mergeable_name #ty_generics #merge_where_clause {
+            fn merge(&self, rhs: &Self) -> Self {
+                Self {
+                    #(#merge_fields),*
+                }
+            }
+        }
 
-                if field_options.is_nested {
-                    quote! {
-                        #name: merges::nested_merge(&self.#name, &rhs.#name)
-                    }
+        impl #merge_impl_generics #mergeable_name #ty_generics #merge_where_clause {
+            /// Emulates CSS inheritance: fields marked with
+            /// `#[mergeable(inherit)]` fall back to the parent's value
+            /// when they are not set on `self`. All other fields are
+            /// left as they are.
+            #[allow(unused_variables, clippy::needless_update)]
+            pub fn inherit_from(&self, parent: &Self) -> Self {
+                Self {
+                    #(#inherit_fields,)*
+                    ..self.clone()
+                }
+            }
+        }
+    }
+}
+
+fn expand_enum(
+    original_enum: &DataEnum,
+    field_options: &FieldsOptions,
+    generics: &Generics,
+) -> TokenStream {
+    let mergeable_name = Ident::new(MERGEABLE_NAME, Span::call_site());
+    let unmergeable_name = Ident::new(UNMERGEABLE_NAME, Span::call_site());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut to_mergeable = vec![];
+    let mut to_unwrapped = vec![];
+    let mut merge_variants = vec![];
+
+    let merge_generics = with_merge_bounds(
+        generics,
+        original_enum.variants.iter().flat_map(|variant| {
+            variant
+                .fields
+                .iter()
+                .enumerate()
+                .filter(move |(index, field)| {
+                    field_options
+                        .get_by_field(Some(&variant.ident), *index, field)
+                        .is_nested
+                })
+                .map(|(_, field)| field)
+        }),
+    );
+    let (merge_impl_generics, _, merge_where_clause) = merge_generics.split_for_impl();
+
+    for variant in &original_enum.variants {
+        let variant_name = &variant.ident;
+        let is_mergeable_variant = is_mergeable_variant(Some(variant_name), &variant.fields);
+
+        let fields = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                (
+                    field_member(index, field),
+                    format_ident!("field_{}", index),
+                    field_options.get_by_field(Some(variant_name), index, field),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let names = fields.iter().map(|(name, _, _)| name).collect::<Vec<_>>();
+        let bindings = fields
+            .iter()
+            .map(|(_, binding, _)| binding)
+            .collect::<Vec<_>>();
+
+        let (wrapped, unwrapped): (Vec<_>, Vec<_>) = fields
+            .iter()
+            .map(|(_, binding, field_options)| {
+                if !is_mergeable_variant {
+                    (quote! { #binding }, quote! { #binding })
+                } else if field_options.is_nested {
+                    (
+                        quote! { Some(#binding.into()) },
+                        quote! { #binding.unwrap().into() },
+                    )
                 } else {
-                    quote! {
-                        #name: merges::primitive_merge(&self.#name, &rhs.#name)
-                    }
+                    (quote! { Some(#binding) }, quote! { #binding.unwrap() })
                 }
             })
-        } else {
-            unimplemented!()
+            .unzip();
+
+        to_mergeable.push(quote! {
+            #unmergeable_name::#variant_name { #(#names: #bindings),* } =>
+                Self::#variant_name { #(#names: #wrapped),* }
+        });
+
+        to_unwrapped.push(quote! {
+            #mergeable_name::#variant_name { #(#names: #bindings),* } =>
+                Self::#variant_name { #(#names: #unwrapped),* }
+        });
+
+        if is_mergeable_variant {
+            let lhs_bindings = fields
+                .iter()
+                .map(|(_, binding, _)| format_ident!("lhs_{}", binding));
+            let rhs_bindings = fields
+                .iter()
+                .map(|(_, binding, _)| format_ident!("rhs_{}", binding));
+
+            let merge_fields = fields.iter().map(|(name, binding, field_options)| {
+                let lhs = format_ident!("lhs_{}", binding);
+                let rhs = format_ident!("rhs_{}", binding);
+                let merge_fn = field_options.merge_fn();
+
+                quote! { #name: #merge_fn(#lhs, #rhs) }
+            });
+
+            merge_variants.push(quote! {
+                (
+                    Self::#variant_name { #(#names: #lhs_bindings),* },
+                    Self::#variant_name { #(#names: #rhs_bindings),* },
+                ) => Self::#variant_name { #(#merge_fields),* }
+            });
         }
+    }
+
+    // When both sides are the same struct variant they are merged field by
+    // field, otherwise the right hand side replaces the left entirely.
+    let merge_body = if merge_variants.is_empty() {
+        quote! { rhs.clone() }
     } else {
-        unimplemented!()
+        quote! {
+            match (self, rhs) {
+                #(#merge_variants,)*
+                _ => rhs.clone(),
+            }
+        }
     };
 
-    let to_mergeable = if let Data::Struct(s) = &original_ast.data {
-        if let Fields::Named(named_fields) = &s.fields {
-            named_fields.named.iter().map(|field| {
-                let field_options = field_options.get_by_field(field);
-                let is_nested = field_options.is_nested;
-
-                let name = field.clone().ident;
+    quote! {
+        impl #impl_generics From<#unmergeable_name #ty_generics> for #mergeable_name #ty_generics #where_clause {
+            fn from(orig: #unmergeable_name #ty_generics) -> Self {
+                match orig {
+                    #(#to_mergeable),*
+                }
+            }
+        }
 
-                if is_nested {
-                    quote! {
-                        #name: Some(orig.#name.into())
-                    }
-                } else {
-                    quote! {
-                        #name: Some(orig.#name)
-                    }
+        impl #impl_generics From<#mergeable_name #ty_generics> for #unmergeable_name #ty_generics #where_clause {
+            fn from(orig: #mergeable_name #ty_generics) -> Self {
+                match orig {
+                    #(#to_unwrapped),*
                 }
-            })
-        } else {
-            unimplemented!()
+            }
         }
-    } else {
-        unimplemented!()
-    };
 
-    let to_unwrapped = if let Data::Struct(s) = &original_ast.data {
-        if let Fields::Named(named_fields) = &s.fields {
-            named_fields.named.iter().map(|field| {
-                let field_options = field_options.get_by_field(field);
-                let is_nested = field_options.is_nested;
-                let name = field.clone().ident;
-
-                if is_nested {
-                    quote! {
-                        #name: orig.#name.unwrap().into()
-                    }
-                } else {
-                    quote! {
-                        #name: orig.#name.unwrap()
-                    }
+        impl #merge_impl_generics Merges for #mergeable_name #ty_generics #merge_where_clause {
+            fn merge(&self, rhs: &Self) -> Self {
+                #merge_body
+            }
+        }
+    }
+}
+
+/// The traits derived on the item, i.e. `Clone` and `Debug` for
+/// `#[derive(Clone, Debug)]`.
+fn derived_traits(ast: &DeriveInput) -> syn::Result<Vec<Path>> {
+    let mut traits = vec![];
+
+    for attr in ast.attrs.iter().filter(|attr| attr.path.is_ident("derive")) {
+        if let Meta::List(derives) = attr.parse_meta()? {
+            for derive in derives.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = derive {
+                    traits.push(path);
                 }
-            })
-        } else {
-            unimplemented!()
+            }
         }
-    } else {
-        unimplemented!()
+    }
+
+    Ok(traits)
+}
+
+fn is_trait(path: &Path, name: &str) -> bool {
+    path.segments
+        .last()
+        .into_iter()
+        .any(|segment| segment.ident == name)
+}
+
+/// Both generated types derive `TS` so they can be exported with their own
+/// names. Deriving it on the item as well would conflict with that.
+fn reject_ts_derive(derived_traits: &[Path]) -> syn::Result<()> {
+    let mut errors = Errors::default();
+
+    for path in derived_traits.iter().filter(|path| is_trait(path, "TS")) {
+        errors.push(syn::Error::new_spanned(
+            path,
+            "#[mergeable] already derives `TS` for the generated types",
+        ));
+    }
+
+    errors.finish(())
+}
+
+fn expand_mergeable(
+    attr_args: AttributeArgs,
+    mut original_ast: DeriveInput,
+) -> syn::Result<TokenStream> {
+    let mut errors = Errors::default();
+
+    let global_options = GlobalOptions::from_list(&attr_args)
+        .map_err(|error| errors.push(error.into()))
+        .unwrap_or_default();
+
+    let derived_traits = derived_traits(&original_ast)?;
+
+    if let Err(error) = reject_ts_derive(&derived_traits) {
+        errors.push(error);
+    }
+
+    let field_options = extract_field_attrs(&mut original_ast)
+        .map_err(|error| errors.push(error))
+        .unwrap_or_default();
+
+    errors.finish(())?;
+
+    let mut mergeable_ast = original_ast.clone();
+    let original_name = original_ast.ident.clone();
+
+    let mergeable_name = syn::Ident::new(MERGEABLE_NAME, Span::call_site());
+    let unmergeable_name = syn::Ident::new(UNMERGEABLE_NAME, Span::call_site());
+
+    mergeable_ast.ident = mergeable_name;
+    original_ast.ident = unmergeable_name;
+
+    convert_fields_to_optional(&mut mergeable_ast, &field_options, &global_options)?;
+    convert_nested_fields(&mut original_ast, &field_options, &global_options)?;
+
+    let impls = match &original_ast.data {
+        Data::Struct(original_struct) => {
+            expand_struct(original_struct, &field_options, &original_ast.generics)
+        }
+        Data::Enum(original_enum) => {
+            expand_enum(original_enum, &field_options, &original_ast.generics)
+        }
+        Data::Union(_) => unreachable!("Unions are rejected by extract_field_attrs"),
     };
 
     original_ast.vis = parse_quote! { pub };
     mergeable_ast.vis = parse_quote! { pub };
 
-    let rename_as = LitStr::new(&original_name.to_string(), Span::call_site());
+    // The Mergeable form is what documents are written with so it gets the
+    // plain name, i.e. `Style` and `ResolvedStyle`.
+    let ts_name = global_options
+        .rename
+        .clone()
+        .unwrap_or_else(|| original_name.to_string());
+    let mergeable_ts_name = LitStr::new(&ts_name, Span::call_site());
+    let unmergeable_ts_name = LitStr::new(
+        &format!("{}{}", UNMERGEABLE_TS_PREFIX, ts_name),
+        Span::call_site(),
+    );
 
     mergeable_ast
         .attrs
-        .push(parse_quote! { #[ts(rename = #rename_as)] });
+        .push(parse_quote! { #[ts(rename = #mergeable_ts_name)] });
+    original_ast
+        .attrs
+        .push(parse_quote! { #[ts(rename = #unmergeable_ts_name)] });
+
+    // JSON schemas are named the same way, otherwise every definition would
+    // be called `Mergeable` or `Unmergeable`
+    if derived_traits
+        .iter()
+        .any(|path| is_trait(path, "JsonSchema"))
+    {
+        mergeable_ast
+            .attrs
+            .push(parse_quote! { #[schemars(rename = #mergeable_ts_name)] });
+        original_ast
+            .attrs
+            .push(parse_quote! { #[schemars(rename = #unmergeable_ts_name)] });
+    }
 
-    let token_stream = quote! {
+    Ok(quote! {
 
         #[allow(non_snake_case)]
         pub mod #original_name {
@@ -187,37 +570,26 @@ pub fn mergeable(
             use merges::Merges;
             use serde::Deserialize;
 
-            #[derive(Deserialize)]
+            #[derive(Deserialize, ts_rs::TS)]
             #original_ast
 
-            #[derive(Default, Deserialize)]
+            #[derive(Deserialize, ts_rs::TS)]
             #mergeable_ast
 
-            impl From<#unmergeable_name> for #mergeable_name {
-                fn from(orig: #unmergeable_name) -> Self {
-                    Self {
-                        #(#to_mergeable),*
-                    }
-                }
-            }
-
-            impl From<#mergeable_name> for #unmergeable_name {
-                fn from(orig: #mergeable_name) -> Self {
-                    Self {
-                        #(#to_unwrapped),*
-                    }
-                }
-            }
-
-            impl Merges for #mergeable_name {
-                fn merge(&self, rhs: &Self) -> Self {
-                    Self {
-                        #(#merge_fields),*
-                    }
-                }
-            }
+            #impls
         }
-    };
+    })
+}
+
+#[proc_macro_attribute]
+pub fn mergeable(
+    attr: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let attr_args = parse_macro_input!(attr as AttributeArgs);
+    let original_ast = parse_macro_input!(input as DeriveInput);
 
-    token_stream.into()
+    expand_mergeable(attr_args, original_ast)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
 }
@@ -0,0 +1,47 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// How a non-nested field is merged, set with one of the named strategies in
+/// `#[mergeable(strategy = "...")]`, or with `#[mergeable(with = "path")]` to
+/// a function with the same signature as `merges::primitive_merge`.
+#[derive(Clone, Debug)]
+pub enum MergeStrategy {
+    Replace,
+    Append,
+    DeepMerge,
+    Function(syn::Path),
+}
+
+impl Default for MergeStrategy {
+    fn default() -> Self {
+        Self::Replace
+    }
+}
+
+/// `from_value` adds the literal's span to the error
+impl FromMeta for MergeStrategy {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "replace" => Ok(Self::Replace),
+            "append" => Ok(Self::Append),
+            "deep_merge" => Ok(Self::DeepMerge),
+            _ => Err(darling::Error::custom(format!(
+                "unknown merge strategy `{}`, expected `replace`, `append` or `deep_merge` \
+                 (use `with = \"path\"` to merge with a function)",
+                value
+            ))),
+        }
+    }
+}
+
+impl MergeStrategy {
+    pub fn merge_fn(&self) -> TokenStream {
+        match self {
+            Self::Replace => quote! { merges::primitive_merge },
+            Self::Append => quote! { merges::append_merge },
+            Self::DeepMerge => quote! { merges::deep_merge },
+            Self::Function(path) => quote! { #path },
+        }
+    }
+}
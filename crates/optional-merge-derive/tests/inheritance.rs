@@ -0,0 +1,94 @@
+use merges::Merges;
+use optional_merge_derive::mergeable;
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+struct Font {
+    family: String,
+    size: f64,
+}
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+struct TextStyle {
+    #[mergeable(nested, inherit)]
+    font: Font,
+    #[mergeable(inherit)]
+    color: String,
+    #[mergeable(inherit)]
+    line_height: Option<f64>,
+    width: String,
+    #[mergeable(nested)]
+    margin: Font,
+}
+
+fn parent_style() -> TextStyle::Mergeable {
+    TextStyle::Mergeable {
+        font: Some(Font::Mergeable {
+            family: Some("Inter".to_owned()),
+            size: Some(12.),
+        }),
+        color: Some("red".to_owned()),
+        line_height: Some(Some(1.5)),
+        width: Some("100%".to_owned()),
+        margin: Some(Font::Mergeable {
+            family: Some("margin".to_owned()),
+            ..Default::default()
+        }),
+    }
+}
+
+#[test]
+fn inherits_unset_fields() {
+    let child = TextStyle::Mergeable::default().inherit_from(&parent_style());
+
+    assert_eq!(child.color, Some("red".to_owned()));
+    assert_eq!(child.line_height, Some(Some(1.5)));
+    assert_eq!(
+        child.font,
+        Some(Font::Mergeable {
+            family: Some("Inter".to_owned()),
+            size: Some(12.),
+        })
+    );
+}
+
+#[test]
+fn does_not_inherit_unmarked_fields() {
+    let child = TextStyle::Mergeable::default().inherit_from(&parent_style());
+
+    assert_eq!(child.width, None);
+    assert_eq!(child.margin, None);
+}
+
+#[test]
+fn child_fields_win() {
+    let child = TextStyle::Mergeable {
+        color: Some("blue".to_owned()),
+        line_height: Some(None),
+        font: Some(Font::Mergeable {
+            size: Some(20.),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+    .inherit_from(&parent_style());
+
+    assert_eq!(child.color, Some("blue".to_owned()));
+    assert_eq!(child.line_height, Some(None));
+    // Nested inherited fields are merged so only the fields the child sets win
+    assert_eq!(
+        child.font,
+        Some(Font::Mergeable {
+            family: Some("Inter".to_owned()),
+            size: Some(20.),
+        })
+    );
+}
+
+#[test]
+fn inheritance_is_independent_of_merge() {
+    let merged = TextStyle::Mergeable::default().merge(&parent_style());
+
+    assert_eq!(merged, parent_style());
+}
@@ -0,0 +1,167 @@
+use merges::Merges;
+use optional_merge_derive::mergeable;
+use ts_rs::TS;
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+struct Size(f64, String);
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+struct Edges<T: TS> {
+    top: T,
+    bottom: T,
+}
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+struct Extension<T: TS> {
+    #[mergeable(nested)]
+    inner: T,
+    name: String,
+}
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+enum Fill {
+    None,
+    Solid(String),
+    Stripes {
+        color: String,
+        #[mergeable(nested)]
+        spacing: Edges<f64>,
+    },
+}
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+struct Background {
+    #[mergeable(nested)]
+    fill: Fill,
+    #[mergeable(nested)]
+    insets: Edges<f64>,
+}
+
+#[test]
+fn merges_tuple_structs() {
+    let lhs = Size::Mergeable(Some(12.), Some("pt".to_owned()));
+    let rhs = Size::Mergeable(None, Some("px".to_owned()));
+
+    let merged: Size::Unmergeable = lhs.merge(&rhs).into();
+
+    assert_eq!(merged, Size::Unmergeable(12., "px".to_owned()));
+}
+
+#[test]
+fn merges_generic_structs() {
+    let lhs = Edges::Mergeable {
+        top: Some(1.),
+        bottom: Some(2.),
+    };
+    let rhs = Edges::Mergeable {
+        top: Some(3.),
+        ..Default::default()
+    };
+
+    let merged: Edges::Unmergeable<f64> = lhs.merge(&rhs).into();
+
+    assert_eq!(
+        merged,
+        Edges::Unmergeable {
+            top: 3.,
+            bottom: 2.
+        }
+    );
+}
+
+#[test]
+fn merges_nested_type_parameters() {
+    let lhs = Extension::Mergeable {
+        inner: Some(Edges::Mergeable {
+            top: Some(1.),
+            bottom: Some(2.),
+        }),
+        name: Some("lhs".to_owned()),
+    };
+    let rhs = Extension::Mergeable {
+        inner: Some(Edges::Mergeable {
+            bottom: Some(4.),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let merged = lhs.merge(&rhs);
+
+    assert_eq!(
+        merged.inner,
+        Some(Edges::Mergeable {
+            top: Some(1.),
+            bottom: Some(4.),
+        })
+    );
+    assert_eq!(merged.name, Some("lhs".to_owned()));
+}
+
+#[test]
+fn merges_matching_struct_variants_field_by_field() {
+    let lhs = Fill::Mergeable::Stripes {
+        color: Some("red".to_owned()),
+        spacing: Some(Edges::Mergeable {
+            top: Some(1.),
+            bottom: Some(1.),
+        }),
+    };
+    let rhs = Fill::Mergeable::Stripes {
+        color: None,
+        spacing: Some(Edges::Mergeable {
+            top: Some(5.),
+            ..Default::default()
+        }),
+    };
+
+    let merged: Fill::Unmergeable = lhs.merge(&rhs).into();
+
+    assert_eq!(
+        merged,
+        Fill::Unmergeable::Stripes {
+            color: "red".to_owned(),
+            spacing: Edges::Unmergeable {
+                top: 5.,
+                bottom: 1.
+            },
+        }
+    );
+}
+
+#[test]
+fn replaces_other_variants() {
+    let stripes = Fill::Mergeable::Stripes {
+        color: Some("red".to_owned()),
+        spacing: None,
+    };
+    let solid = Fill::Mergeable::Solid("blue".to_owned());
+
+    assert_eq!(stripes.merge(&solid), solid);
+    assert_eq!(solid.merge(&Fill::Mergeable::None), Fill::Mergeable::None);
+    assert_eq!(
+        solid.merge(&Fill::Mergeable::Solid("green".to_owned())),
+        Fill::Mergeable::Solid("green".to_owned())
+    );
+}
+
+#[test]
+fn nests_enums_as_options() {
+    let lhs: Background::Mergeable = Background::Unmergeable {
+        fill: Fill::Unmergeable::Solid("red".to_owned()),
+        insets: Edges::Unmergeable {
+            top: 0.,
+            bottom: 0.,
+        },
+    }
+    .into();
+
+    let merged: Background::Unmergeable = lhs.merge(&Default::default()).into();
+
+    assert_eq!(merged.fill, Fill::Unmergeable::Solid("red".to_owned()));
+}
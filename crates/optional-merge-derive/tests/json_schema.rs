@@ -0,0 +1,44 @@
+use optional_merge_derive::mergeable;
+use schemars::{schema_for, JsonSchema};
+
+#[mergeable]
+#[derive(Clone, JsonSchema)]
+struct Font {
+    family: String,
+}
+
+#[mergeable]
+#[derive(Clone, JsonSchema)]
+struct Style {
+    #[mergeable(nested)]
+    font: Font,
+    width: f64,
+}
+
+#[test]
+fn names_both_forms() {
+    assert_eq!(Style::Mergeable::schema_name(), "Style");
+    assert_eq!(Style::Unmergeable::schema_name(), "ResolvedStyle");
+
+    let schema = schema_for!(Style::Mergeable);
+
+    assert!(schema.definitions.contains_key("Font"));
+}
+
+#[test]
+fn only_unmergeable_fields_are_required() {
+    let mergeable = schema_for!(Style::Mergeable);
+    let unmergeable = schema_for!(Style::Unmergeable);
+
+    assert!(mergeable.schema.object.unwrap().required.is_empty());
+    assert_eq!(
+        unmergeable
+            .schema
+            .object
+            .unwrap()
+            .required
+            .into_iter()
+            .collect::<Vec<_>>(),
+        ["font", "width"]
+    );
+}
@@ -1,23 +1,6 @@
-// use optional_merge_derive::mergeable;
+#[test]
+fn compile_fail() {
+    let tests = trybuild::TestCases::new();
 
-// #[mergeable]
-// #[derive(Debug, Clone)]
-// struct NestedStruct {}
-
-// #[mergeable]
-// #[derive(Debug, Clone)]
-// struct TestStruct {
-//     bool_test: bool,
-//     #[mergeable(nested)]
-//     nested_struct: NestedStruct,
-// }
-
-// #[test]
-// fn test_unmerged() {
-//     let _original = TestStruct::Unmergeable {
-//         bool_test: true,
-//         nested_struct: NestedStruct::Unmergeable {},
-//     };
-
-//     let _merged = TestStruct::Mergeable::default();
-// }
+    tests.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use merges::Merges;
+use optional_merge_derive::mergeable;
+use ts_rs::TS;
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+struct Shadow {
+    blur: f64,
+    color: String,
+}
+
+fn larger(lhs: &Option<f64>, rhs: &Option<f64>) -> Option<f64> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs.max(*rhs)),
+        _ => lhs.or(*rhs),
+    }
+}
+
+#[mergeable(rename = "TextStyle")]
+#[derive(Clone, Debug, PartialEq)]
+struct Style {
+    #[mergeable(strategy = "append")]
+    font_features: Vec<String>,
+    #[mergeable(strategy = "deep_merge")]
+    shadows: HashMap<String, Shadow::Mergeable>,
+    #[mergeable(with = "larger")]
+    min_width: f64,
+    #[mergeable(strategy = "replace")]
+    color: String,
+}
+
+fn shadow(blur: Option<f64>, color: Option<&str>) -> Shadow::Mergeable {
+    Shadow::Mergeable {
+        blur,
+        color: color.map(str::to_owned),
+    }
+}
+
+fn features(features: &[&str]) -> Option<Vec<String>> {
+    Some(features.iter().map(|&feature| feature.to_owned()).collect())
+}
+
+#[test]
+fn appends_lists() {
+    let lhs = Style::Mergeable {
+        font_features: features(&["liga"]),
+        ..Default::default()
+    };
+    let rhs = Style::Mergeable {
+        font_features: features(&["tnum", "smcp"]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        lhs.merge(&rhs).font_features,
+        features(&["liga", "tnum", "smcp"])
+    );
+    assert_eq!(
+        lhs.merge(&Default::default()).font_features,
+        features(&["liga"])
+    );
+}
+
+#[test]
+fn deep_merges_maps() {
+    let lhs = Style::Mergeable {
+        shadows: Some(HashMap::from([
+            ("card".to_owned(), shadow(Some(4.), Some("black"))),
+            ("button".to_owned(), shadow(Some(1.), None)),
+        ])),
+        ..Default::default()
+    };
+    let rhs = Style::Mergeable {
+        shadows: Some(HashMap::from([
+            ("card".to_owned(), shadow(None, Some("grey"))),
+            ("modal".to_owned(), shadow(Some(8.), None)),
+        ])),
+        ..Default::default()
+    };
+
+    let shadows = lhs.merge(&rhs).shadows.unwrap();
+
+    assert_eq!(shadows["card"], shadow(Some(4.), Some("grey")));
+    assert_eq!(shadows["button"], shadow(Some(1.), None));
+    assert_eq!(shadows["modal"], shadow(Some(8.), None));
+}
+
+#[test]
+fn merges_with_functions() {
+    let wide = Style::Mergeable {
+        min_width: Some(200.),
+        color: Some("red".to_owned()),
+        ..Default::default()
+    };
+    let narrow = Style::Mergeable {
+        min_width: Some(100.),
+        color: Some("blue".to_owned()),
+        ..Default::default()
+    };
+
+    let merged = wide.merge(&narrow);
+
+    assert_eq!(merged.min_width, Some(200.));
+    assert_eq!(merged.color, Some("blue".to_owned()));
+}
+
+#[test]
+fn renames_exported_types() {
+    assert_eq!(Style::Mergeable::name(), "TextStyle");
+    assert_eq!(Style::Unmergeable::name(), "ResolvedTextStyle");
+}
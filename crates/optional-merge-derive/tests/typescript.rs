@@ -0,0 +1,48 @@
+use optional_merge_derive::mergeable;
+use ts_rs::TS;
+
+#[mergeable]
+#[derive(Clone)]
+struct Font {
+    family: String,
+}
+
+#[mergeable]
+#[derive(Clone)]
+#[ts(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+struct Style {
+    #[mergeable(nested)]
+    font: Font,
+    line_height: f64,
+}
+
+#[test]
+fn names_both_forms() {
+    assert_eq!(Style::Mergeable::name(), "Style");
+    assert_eq!(Style::Unmergeable::name(), "ResolvedStyle");
+}
+
+#[test]
+fn mergeable_fields_are_optional() {
+    let mergeable = Style::Mergeable::decl();
+
+    assert!(mergeable.contains("font?: Font"), "{}", mergeable);
+    assert!(mergeable.contains("lineHeight?: number"), "{}", mergeable);
+}
+
+#[test]
+fn unmergeable_fields_are_required() {
+    let unmergeable = Style::Unmergeable::decl();
+
+    assert!(
+        unmergeable.contains("font: ResolvedFont"),
+        "{}",
+        unmergeable
+    );
+    assert!(
+        unmergeable.contains("lineHeight: number"),
+        "{}",
+        unmergeable
+    );
+}
@@ -0,0 +1,10 @@
+use optional_merge_derive::mergeable;
+use ts_rs::TS;
+
+#[mergeable]
+#[derive(TS, Clone)]
+struct Style {
+    color: String,
+}
+
+fn main() {}
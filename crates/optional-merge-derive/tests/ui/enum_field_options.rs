@@ -0,0 +1,18 @@
+use optional_merge_derive::mergeable;
+
+#[mergeable]
+#[derive(Clone)]
+struct Stripes {
+    spacing: f64,
+}
+
+#[mergeable]
+enum Fill {
+    Pattern(#[mergeable(nested)] Stripes),
+    Solid {
+        #[mergeable(inherit)]
+        color: String,
+    },
+}
+
+fn main() {}
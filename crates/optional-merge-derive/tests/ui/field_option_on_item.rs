@@ -0,0 +1,8 @@
+use optional_merge_derive::mergeable;
+
+#[mergeable(strategy = "append")]
+struct Style {
+    color: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use optional_merge_derive::mergeable;
+
+#[mergeable]
+struct Style {
+    #[mergeable(nested)]
+    size: (f64, f64),
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use optional_merge_derive::mergeable;
+
+#[mergeable]
+#[derive(Clone)]
+struct Font {
+    family: String,
+}
+
+#[mergeable]
+struct Style {
+    #[mergeable(nested, strategy = "append")]
+    font: Font,
+}
+
+fn main() {}
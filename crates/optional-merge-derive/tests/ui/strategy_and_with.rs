@@ -0,0 +1,13 @@
+use optional_merge_derive::mergeable;
+
+fn larger(lhs: &Option<u32>, rhs: &Option<u32>) -> Option<u32> {
+    lhs.max(rhs).clone()
+}
+
+#[mergeable]
+struct Style {
+    #[mergeable(strategy = "replace", with = "larger")]
+    size: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use optional_merge_derive::mergeable;
+
+#[mergeable]
+union Size {
+    pt: f64,
+    px: u32,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use optional_merge_derive::mergeable;
+
+#[mergeable]
+#[derive(Clone)]
+struct Font {
+    family: String,
+}
+
+#[mergeable]
+struct Style {
+    #[mergeable(nestd)]
+    font: Font,
+    #[mergeable(inherit = "yes")]
+    color: String,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use optional_merge_derive::mergeable;
+
+#[mergeable(flatten)]
+struct Style {
+    color: String,
+}
+
+fn main() {}
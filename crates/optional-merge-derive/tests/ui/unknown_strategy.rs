@@ -0,0 +1,9 @@
+use optional_merge_derive::mergeable;
+
+#[mergeable]
+struct Style {
+    #[mergeable(strategy = "replce")]
+    color: String,
+}
+
+fn main() {}
@@ -0,0 +1,2 @@
+
+export type BackgroundRepeat = "Repeat" | "RepeatX" | "RepeatY" | "NoRepeat";
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export type BorderLineStyle = "Solid" | "Dashed" | "Dotted" | "Double";
\ No newline at end of file
@@ -1,2 +1,2 @@
 
-export interface BorderRadiusStyle { topRight?: string | number, bottomRight?: string | number, bottomLeft?: string | number, topLeft?: string | number, }
\ No newline at end of file
+export interface BorderRadiusStyle { topRight?: string | number | { horizontal: string | number, vertical: string | number }, bottomRight?: string | number | { horizontal: string | number, vertical: string | number }, bottomLeft?: string | number | { horizontal: string | number, vertical: string | number }, topLeft?: string | number | { horizontal: string | number, vertical: string | number }, }
\ No newline at end of file
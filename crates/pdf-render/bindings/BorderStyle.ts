@@ -1,4 +1,5 @@
 import type { BorderRadiusStyle } from "./BorderRadiusStyle";
+import type { EdgeLineStyle } from "./EdgeLineStyle";
 import type { EdgeStyle } from "./EdgeStyle";
 
-export interface BorderStyle { width?: EdgeStyle, color?: string, radius?: BorderRadiusStyle, }
\ No newline at end of file
+export interface BorderStyle { width?: EdgeStyle, color?: string | { top?: string, right?: string, bottom?: string, left?: string }, style?: EdgeLineStyle, radius?: BorderRadiusStyle, }
\ No newline at end of file
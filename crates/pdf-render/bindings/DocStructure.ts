@@ -1,4 +1,7 @@
 import type { DomNode } from "./DomNode";
 import type { FontFamilyInfo } from "./FontFamilyInfo";
+import type { OutputIntentInfo } from "./OutputIntentInfo";
+import type { ResolvedEdgeStyle } from "./ResolvedEdgeStyle";
+import type { StylesheetImport } from "./StylesheetImport";
 
-export interface DocStructure { filename: string, documentTitle: string, pageSize: string, pageMargins: EdgeStyle, fonts: Array<FontFamilyInfo>, stylesheet: Record<string, Style>, root: DomNode, }
\ No newline at end of file
+export interface DocStructure { filename: string, documentTitle: string, pageSize: string, pageMargins: ResolvedEdgeStyle, dpi: number, lang: string | null, fonts: Array<FontFamilyInfo>, outputIntent: OutputIntentInfo | null, stylesheetImports: Array<StylesheetImport>, stylesheet: Record<string, Style> & { variables?: Record<string, string | number> }, root: DomNode, }
\ No newline at end of file
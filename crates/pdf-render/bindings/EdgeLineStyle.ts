@@ -0,0 +1,3 @@
+import type { BorderLineStyle } from "./BorderLineStyle";
+
+export interface EdgeLineStyle { top?: BorderLineStyle, right?: BorderLineStyle, bottom?: BorderLineStyle, left?: BorderLineStyle, }
\ No newline at end of file
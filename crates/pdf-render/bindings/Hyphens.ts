@@ -0,0 +1,2 @@
+
+export type Hyphens = "None" | "Manual" | "Auto";
\ No newline at end of file
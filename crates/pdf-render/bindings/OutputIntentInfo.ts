@@ -0,0 +1,2 @@
+
+export interface OutputIntentInfo { source: string, identifier: string, info: string | null, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export type Overflow = "Visible" | "Hidden";
\ No newline at end of file
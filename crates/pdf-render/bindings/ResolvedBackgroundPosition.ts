@@ -0,0 +1,2 @@
+
+export interface ResolvedBackgroundPosition { x: "Start" | "Center" | "End" | number | string, y: "Start" | "Center" | "End" | number | string, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export interface ResolvedBorderRadiusStyle { topRight: string | number | { horizontal: string | number, vertical: string | number }, bottomRight: string | number | { horizontal: string | number, vertical: string | number }, bottomLeft: string | number | { horizontal: string | number, vertical: string | number }, topLeft: string | number | { horizontal: string | number, vertical: string | number }, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+import type { ResolvedBorderRadiusStyle } from "./ResolvedBorderRadiusStyle";
+import type { ResolvedEdgeLineStyle } from "./ResolvedEdgeLineStyle";
+import type { ResolvedEdgeStyle } from "./ResolvedEdgeStyle";
+
+export interface ResolvedBorderStyle { width: ResolvedEdgeStyle, color: string | { top?: string, right?: string, bottom?: string, left?: string }, style: ResolvedEdgeLineStyle, radius: ResolvedBorderRadiusStyle, }
\ No newline at end of file
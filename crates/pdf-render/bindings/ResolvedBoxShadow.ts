@@ -0,0 +1,2 @@
+
+export interface ResolvedBoxShadow { offsetX: string | number, offsetY: string | number, blur: string | number, spread: string | number, color: string, inset: boolean, }
\ No newline at end of file
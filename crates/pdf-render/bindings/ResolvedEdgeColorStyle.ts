@@ -0,0 +1,2 @@
+
+export interface ResolvedEdgeColorStyle { top: string, right: string, bottom: string, left: string, }
\ No newline at end of file
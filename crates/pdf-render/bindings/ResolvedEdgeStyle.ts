@@ -0,0 +1,2 @@
+
+export interface ResolvedEdgeStyle { top: string | number, right: string | number, bottom: string | number, left: string | number, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+import type { Direction } from "./Direction";
+import type { FlexAlign } from "./FlexAlign";
+import type { FlexWrap } from "./FlexWrap";
+
+export interface ResolvedFlexStyle { direction: Direction, wrap: FlexWrap, align_items: FlexAlign, align_self: FlexAlign, grow: number, shrink: number, }
\ No newline at end of file
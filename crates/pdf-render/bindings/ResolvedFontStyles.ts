@@ -0,0 +1,4 @@
+import type { FontSlant } from "./FontSlant";
+import type { FontWeight } from "./FontWeight";
+
+export interface ResolvedFontStyles { family: string, size: string | number, style: FontSlant, weight: FontWeight, letterSpacing: string | number, }
\ No newline at end of file
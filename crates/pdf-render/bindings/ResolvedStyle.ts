@@ -0,0 +1,17 @@
+import type { BackgroundRepeat } from "./BackgroundRepeat";
+import type { Hyphens } from "./Hyphens";
+import type { Overflow } from "./Overflow";
+import type { PageBreakRule } from "./PageBreakRule";
+import type { ResolvedBackgroundPosition } from "./ResolvedBackgroundPosition";
+import type { ResolvedBorderStyle } from "./ResolvedBorderStyle";
+import type { ResolvedBoxShadow } from "./ResolvedBoxShadow";
+import type { ResolvedEdgeStyle } from "./ResolvedEdgeStyle";
+import type { ResolvedFlexStyle } from "./ResolvedFlexStyle";
+import type { ResolvedFontStyles } from "./ResolvedFontStyles";
+import type { ResolvedTextDecoration } from "./ResolvedTextDecoration";
+import type { TextAlign } from "./TextAlign";
+import type { TextFit } from "./TextFit";
+import type { TextOverflow } from "./TextOverflow";
+import type { TextTransformation } from "./TextTransformation";
+
+export interface ResolvedStyle { border: ResolvedBorderStyle, font: ResolvedFontStyles, color: string, margin: ResolvedEdgeStyle, padding: ResolvedEdgeStyle, backgroundColor: string, background: string, backgroundImage: string, backgroundSize: "Auto" | "Cover" | "Contain" | { width?: string | number, height?: string | number }, backgroundPosition: ResolvedBackgroundPosition, backgroundRepeat: BackgroundRepeat, opacity: number, boxShadow: ResolvedBoxShadow, overflow: Overflow, flex: ResolvedFlexStyle, width: string, height: string, debug: boolean, breakBefore: PageBreakRule, breakAfter: PageBreakRule, breakInside: PageBreakRule, orphans: number, widows: number, textTransform: TextTransformation, smartTypography: boolean, hyphens: Hyphens, textAlign: TextAlign, textDecoration: ResolvedTextDecoration, verticalAlign: "Baseline" | "Super" | "Sub" | number | string, lineHeight: number | string, maxLines: number, textOverflow: TextOverflow, fit: TextFit, minFontSize: number | string, }
\ No newline at end of file
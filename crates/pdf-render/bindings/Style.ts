@@ -1,8 +1,17 @@
+import type { BackgroundPosition } from "./BackgroundPosition";
+import type { BackgroundRepeat } from "./BackgroundRepeat";
 import type { BorderStyle } from "./BorderStyle";
+import type { BoxShadow } from "./BoxShadow";
 import type { EdgeStyle } from "./EdgeStyle";
 import type { FlexStyle } from "./FlexStyle";
 import type { FontStyles } from "./FontStyles";
+import type { Hyphens } from "./Hyphens";
+import type { Overflow } from "./Overflow";
 import type { PageBreakRule } from "./PageBreakRule";
+import type { TextAlign } from "./TextAlign";
+import type { TextDecoration } from "./TextDecoration";
+import type { TextFit } from "./TextFit";
+import type { TextOverflow } from "./TextOverflow";
 import type { TextTransformation } from "./TextTransformation";
 
-export interface Style { border?: BorderStyle, font?: FontStyles, color?: string, margin?: EdgeStyle, padding?: EdgeStyle, backgroundColor?: string, flex?: FlexStyle, width?: string, height?: string, debug?: boolean, breakBefore?: PageBreakRule, breakAfter?: PageBreakRule, breakInside?: PageBreakRule, textTransform?: TextTransformation, lineHeight?: number | string, }
\ No newline at end of file
+export interface Style { border?: BorderStyle, font?: FontStyles, color?: string, margin?: EdgeStyle, padding?: EdgeStyle, backgroundColor?: string, background?: string, backgroundImage?: string, backgroundSize?: "Auto" | "Cover" | "Contain" | { width?: string | number, height?: string | number }, backgroundPosition?: BackgroundPosition, backgroundRepeat?: BackgroundRepeat, opacity?: number, boxShadow?: BoxShadow, overflow?: Overflow, flex?: FlexStyle, width?: string, height?: string, debug?: boolean, breakBefore?: PageBreakRule, breakAfter?: PageBreakRule, breakInside?: PageBreakRule, orphans?: number, widows?: number, textTransform?: TextTransformation, smartTypography?: boolean, hyphens?: Hyphens, textAlign?: TextAlign, textDecoration?: TextDecoration, verticalAlign?: "Baseline" | "Super" | "Sub" | number | string, lineHeight?: number | string, maxLines?: number, textOverflow?: TextOverflow, fit?: TextFit, minFontSize?: number | string, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export type StylesheetImport = { type: "File", path: string, } | { type: "Registered", name: string, };
@@ -0,0 +1,2 @@
+
+export type TextAlign = "Start" | "End" | "Left" | "Right" | "Center" | "Justify";
\ No newline at end of file
@@ -0,0 +1,4 @@
+import type { TextDecorationLine } from "./TextDecorationLine";
+import type { TextDecorationStyle } from "./TextDecorationStyle";
+
+export interface TextDecoration { line?: TextDecorationLine, color?: string, style?: TextDecorationStyle, thickness?: string | number, }
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export type TextDecorationLine = "None" | "Underline" | "Overline" | "LineThrough";
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export type TextDecorationStyle = "Solid" | "Dashed" | "Double";
\ No newline at end of file
@@ -0,0 +1,2 @@
+
+export type TextFit = "None" | "Shrink";
\ No newline at end of file
@@ -1,3 +1,3 @@
 import type { TextChild } from "./TextChild";
 
-export interface TextNode { styles: Array<string>, children: Array<TextChild>, }
\ No newline at end of file
+export interface TextNode { styles: Array<string>, lang: string | null, children: Array<TextChild>, }
\ No newline at end of file
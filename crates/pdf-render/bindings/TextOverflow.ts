@@ -0,0 +1,2 @@
+
+export type TextOverflow = "Clip" | "Ellipsis";
\ No newline at end of file
@@ -1,2 +1,2 @@
 
-export type TextTransformation = "None" | "Uppercase";
\ No newline at end of file
+export type TextTransformation = "None" | "Uppercase" | "Lowercase" | "Capitalize" | "SmallCaps";
\ No newline at end of file
@@ -1,5 +0,0 @@
-import type { PageBreakRule } from "./PageBreakRule";
-import type { TextTransformation } from "./TextTransformation";
-import type { Unmergeable } from "./Unmergeable";
-
-export interface Unmergeable { border: Unmergeable, font: Unmergeable, color: string, margin: Unmergeable, padding: Unmergeable, backgroundColor: string, flex: Unmergeable, width: string, height: string, debug: boolean, breakBefore: PageBreakRule, breakAfter: PageBreakRule, breakInside: PageBreakRule, textTransform: TextTransformation, lineHeight: number | string, }
\ No newline at end of file
@@ -4,7 +4,7 @@ use crate::{
     doc_structure::{DomNode, NodeId},
     error::DocumentGenerationError,
     paragraph_layout::ParagraphLayout,
-    stylesheet::Stylesheet,
+    stylesheet::ResolvedStylesheet,
     values::Pt,
 };
 
@@ -40,7 +40,7 @@ pub trait LayoutEngine {
         &mut self,
         page_width: Pt,
         root_node: &DomNode,
-        stylesheet: &Stylesheet,
+        stylesheet: &ResolvedStylesheet,
         paragraph_layout: Rc<ParagraphLayout>,
     ) -> Result<(), DocumentGenerationError>;
 
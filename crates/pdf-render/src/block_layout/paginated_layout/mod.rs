@@ -5,16 +5,22 @@ mod paginated_node;
 
 use draw_cursor::DrawCursor;
 
-pub use paginated_node::{DrawableContainerNode, DrawableNode, DrawableTextNode, PaginatedNode};
+pub use paginated_node::{
+    ClipGroup, DrawableContainerNode, DrawableNode, DrawableTextNode, NodeGroup, OpacityGroup,
+    PaginatedNode,
+};
 
 use crate::{
     doc_structure::{DomNode, HasNodeId, NodeId},
     error::DocumentGenerationError,
     paragraph_layout::{ParagraphLayout, ParagraphStyle, RenderedTextBlock},
     rich_text::dom_node_conversion::dom_node_to_rich_text,
-    stylesheet::{Direction, FlexWrap, PageBreakRule, Style, Stylesheet},
+    stylesheet::{
+        BorderRadiusStyle, Direction, EdgeStyle, FlexWrap, Overflow, PageBreakRule,
+        ResolvedStylesheet, Style, TextFit,
+    },
     utils::{debug_cursor::DebugCursor, node_lookup::NodeLookup, tree_iter::TreeNode},
-    values::{Point, Pt},
+    values::{Point, Pt, Size},
 };
 
 use self::layout_visitor::LayoutVisitor;
@@ -23,26 +29,41 @@ use super::layout_engine::{LayoutEngine, NodeLayout};
 
 pub struct PaginatedLayoutEngine<'a> {
     node_avoids_page_break: HashMap<NodeId, bool>,
+    /// Where the nodes with `overflow: hidden` were placed, for clipping their
+    /// descendants
+    clip_nodes: HashMap<NodeId, ClipNode>,
     node_lookup: &'a NodeLookup<'a>,
     paginated_nodes: Vec<PaginatedNode>,
     paragraph_layout: &'a ParagraphLayout,
     layout_engine: &'a dyn LayoutEngine,
-    stylesheet: &'a Stylesheet,
+    stylesheet: &'a ResolvedStylesheet,
     page_height: Pt,
     pub debug_cursors: Vec<DebugCursor>,
 }
 
+/// Where a node with `overflow: hidden` starts, for finding the part of it
+/// on each page its descendants are drawn on
+struct ClipNode {
+    page_index: usize,
+    page_layout: NodeLayout,
+    /// Where it is in the unpaginated layout
+    layout: NodeLayout,
+    border_width: EdgeStyle::Unmergeable,
+    border_radius: BorderRadiusStyle::Unmergeable,
+}
+
 impl<'a> PaginatedLayoutEngine<'a> {
     pub fn new(
         root_node: &DomNode,
         layout_engine: &'a dyn LayoutEngine,
         node_lookup: &'a NodeLookup,
         paragraph_layout: &'a ParagraphLayout,
-        stylesheet: &'a Stylesheet,
+        stylesheet: &'a ResolvedStylesheet,
         page_height: Pt,
     ) -> Result<Self, DocumentGenerationError> {
         let mut paginated_layout_engine = Self {
             node_avoids_page_break: HashMap::new(),
+            clip_nodes: HashMap::new(),
             node_lookup,
             paragraph_layout,
             paginated_nodes: vec![],
@@ -123,20 +144,38 @@ impl<'a> PaginatedLayoutEngine<'a> {
         // By this point, the draw cursor is in the correct place to start
         // the current node.
 
+        if style.overflow == Overflow::Hidden {
+            self.clip_nodes.insert(
+                node.node_id(),
+                ClipNode {
+                    page_index: draw_cursor.page_index,
+                    page_layout: adjusted_layout.clone(),
+                    layout: node_layout.clone(),
+                    border_width: style.border.width.clone(),
+                    border_radius: style.border.radius.clone(),
+                },
+            );
+        }
+
         let drawable_node = self
             .convert_dom_node_to_drawable(node, &adjusted_layout, &style)
             .unwrap();
 
-        let paginated_node = PaginatedNode {
-            page_layout: adjusted_layout,
-            page_index: draw_cursor.page_index,
-            drawable_node,
-        };
-
-        if let DrawableNode::Text(text_node) = &paginated_node.drawable_node {
-            self.draw_text_node(draw_cursor, &mut style, &mut node_layout, text_node)?;
+        if let DrawableNode::Text(text_node) = &drawable_node {
+            self.draw_text_node(draw_cursor, &mut style, &mut node_layout, text_node, node)?;
         } else {
-            self.paginated_nodes.push(paginated_node);
+            let groups = self.groups(
+                node,
+                draw_cursor.page_index,
+                adjusted_layout.top - node_layout.top,
+            );
+
+            self.paginated_nodes.push(PaginatedNode {
+                page_layout: adjusted_layout,
+                page_index: draw_cursor.page_index,
+                drawable_node,
+                groups,
+            });
         }
 
         Ok(())
@@ -148,6 +187,7 @@ impl<'a> PaginatedLayoutEngine<'a> {
         style: &mut Style::Unmergeable,
         node_layout: &mut NodeLayout,
         text_node: &DrawableTextNode,
+        node: &DomNode,
     ) -> Result<(), DocumentGenerationError> {
         let mut line_offset = 0;
 
@@ -161,9 +201,27 @@ impl<'a> PaginatedLayoutEngine<'a> {
                 })
                 .collect();
 
-            let page_break_index = cumulative_height.iter().position(|&bottom| {
-                bottom + style.padding.top + draw_cursor.y_offset > self.page_height
-            });
+            let page_break_index = cumulative_height
+                .iter()
+                .position(|&bottom| {
+                    bottom + style.padding.top + draw_cursor.y_offset > self.page_height
+                })
+                .map(|lines_that_fit| {
+                    break_between_widows_and_orphans(
+                        lines_that_fit,
+                        cumulative_height.len(),
+                        style,
+                        draw_cursor.y_offset <= Pt(0.),
+                    )
+                });
+
+            // Rather than leave an empty fragment behind, the node starts on
+            // the next page when none of its lines can stay on this one
+            if page_break_index == Some(0) && draw_cursor.y_offset > Pt(0.) {
+                draw_cursor.page_index += 1;
+                draw_cursor.y_offset = Pt(-style.margin.top.0);
+                continue;
+            }
 
             let block_height = match page_break_index {
                 Some(idx) if idx > 0 => cumulative_height[idx - 1],
@@ -191,6 +249,14 @@ impl<'a> PaginatedLayoutEngine<'a> {
                 // ..text_node.text_block.clone()
             };
 
+            // The lines already drawn on earlier pages are what the rest of
+            // the node has moved up by
+            let groups = self.groups(
+                node,
+                draw_cursor.page_index,
+                draw_cursor.y_offset - node_layout.top - draw_cursor.page_break_debt,
+            );
+
             let pn = PaginatedNode {
                 page_layout: NodeLayout {
                     top: draw_cursor.y_offset,
@@ -201,6 +267,7 @@ impl<'a> PaginatedLayoutEngine<'a> {
                     text_block: partial_text_block,
                     style: style.clone(),
                 }),
+                groups,
             };
             node_layout.height -= block_height + style.padding.top;
 
@@ -229,19 +296,34 @@ impl<'a> PaginatedLayoutEngine<'a> {
         let drawable_node = match dom_node {
             DomNode::Text(text_node) => {
                 // FIXME: This should also have already been computed by now
-                let rich_text =
-                    dom_node_to_rich_text(text_node, self.node_lookup, self.stylesheet)?;
+                let rich_text = dom_node_to_rich_text(
+                    text_node,
+                    self.node_lookup,
+                    self.stylesheet,
+                    self.paragraph_layout,
+                )?;
 
                 // FIXME: We already calculated the text block in the yoga layout
                 // engine. Either re-use that or pass it into the layout engine?
-                let text_block = self
-                    .paragraph_layout
-                    .calculate_layout(
-                        ParagraphStyle::left(),
+                let content_size = Size {
+                    width: layout.width - style.padding.horizontal(),
+                    height: layout.height - style.padding.vertical(),
+                };
+
+                let text_block = match style.fit {
+                    TextFit::Shrink => self.paragraph_layout.shrink_to_fit(
+                        ParagraphStyle::from(style),
                         &rich_text,
-                        layout.width - style.padding.horizontal(),
-                    )
-                    .unwrap();
+                        content_size,
+                        style.min_font_size,
+                    ),
+                    TextFit::None => self.paragraph_layout.calculate_layout(
+                        ParagraphStyle::from(style),
+                        &rich_text,
+                        content_size.width,
+                    ),
+                }
+                .unwrap();
 
                 DrawableNode::Text(DrawableTextNode {
                     text_block,
@@ -268,6 +350,69 @@ impl<'a> PaginatedLayoutEngine<'a> {
         }
     }
 
+    /// The groups of the nodes from the root down to `node` itself, as it's
+    /// drawn on `page_index` moved by `page_offset` from where it is in the
+    /// unpaginated layout. A node fades itself along with its subtree, but
+    /// only clips its descendants.
+    fn groups(&self, node: &DomNode, page_index: usize, page_offset: Pt) -> Vec<NodeGroup> {
+        let mut node_ids = self.node_lookup.get_ancestor_ids(node);
+        node_ids.reverse();
+
+        let mut groups = vec![];
+
+        for &node_id in node_ids.iter().chain([node.node_id()].iter()) {
+            let opacity = self.node_lookup.get_style(node_id).opacity;
+
+            if opacity < 1. {
+                groups.push(NodeGroup::Opacity(OpacityGroup { node_id, opacity }));
+            }
+
+            if let Some(clip_node) = self.clip_nodes.get(&node_id) {
+                if node_id != node.node_id() {
+                    groups.push(NodeGroup::Clip(self.clip_group(
+                        node_id,
+                        clip_node,
+                        page_index,
+                        page_offset,
+                    )));
+                }
+            }
+        }
+
+        groups
+    }
+
+    /// The part of a clipping node on `page_index`. Its descendants on later
+    /// pages have been moved by the same page breaks as the rest of the page,
+    /// so it's moved by the same `page_offset` they were.
+    fn clip_group(
+        &self,
+        node_id: NodeId,
+        clip_node: &ClipNode,
+        page_index: usize,
+        page_offset: Pt,
+    ) -> ClipGroup {
+        let starts_on_page = page_index == clip_node.page_index;
+
+        let fragment = if starts_on_page {
+            clip_node.page_layout.clone()
+        } else {
+            NodeLayout {
+                top: clip_node.layout.top + page_offset,
+                ..clip_node.layout.clone()
+            }
+        };
+
+        ClipGroup {
+            node_id,
+            ends_on_page: fragment.bottom() <= self.page_height,
+            starts_on_page,
+            fragment,
+            border_width: clip_node.border_width.clone(),
+            border_radius: clip_node.border_radius.clone(),
+        }
+    }
+
     pub fn paginated_nodes(&self) -> &Vec<PaginatedNode> {
         &self.paginated_nodes
     }
@@ -286,9 +431,143 @@ impl<'a> PaginatedLayoutEngine<'a> {
     }
 }
 
+/// How many of the `line_count` lines remaining in a paragraph to draw on the
+/// current page when only `lines_that_fit` fit, leaving at least
+/// `style.orphans` lines on this page and `style.widows` for the next. When
+/// that isn't possible the whole paragraph moves to the next page, unless the
+/// current page is empty, in which case it gets as many lines as fit.
+fn break_between_widows_and_orphans(
+    lines_that_fit: usize,
+    line_count: usize,
+    style: &Style::Unmergeable,
+    is_page_empty: bool,
+) -> usize {
+    let widow_break = lines_that_fit.min(line_count.saturating_sub(style.widows));
+
+    if widow_break >= style.orphans.max(1) {
+        widow_break
+    } else if is_page_empty {
+        // A line taller than the page is still drawn, clipped by the page
+        lines_that_fit.max(1)
+    } else {
+        0
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::{
+        block_layout::yoga::YogaLayout,
+        doc_structure::TextNode,
+        paragraph_layout::{LineMetrics, RenderedTextLine},
+        rich_text::RichText,
+    };
+
+    use super::*;
 
     #[test]
     fn test_parent_page_break_logic() {}
+
+    #[test]
+    fn honors_widows_and_orphans() {
+        let style = Style::Unmergeable {
+            orphans: 2,
+            widows: 3,
+            ..Default::default()
+        };
+
+        // Fills the page while leaving enough for the next
+        assert_eq!(break_between_widows_and_orphans(4, 10, &style, false), 4);
+        // Carries lines over so they aren't widowed
+        assert_eq!(break_between_widows_and_orphans(8, 10, &style, false), 7);
+        // Moves the whole paragraph rather than orphan a line
+        assert_eq!(break_between_widows_and_orphans(1, 10, &style, false), 0);
+        // Moves the whole paragraph when the two can't both be honored
+        assert_eq!(break_between_widows_and_orphans(3, 4, &style, false), 0);
+        // Unless that wouldn't help
+        assert_eq!(break_between_widows_and_orphans(3, 4, &style, true), 3);
+        assert_eq!(break_between_widows_and_orphans(1, 10, &style, true), 1);
+        assert_eq!(break_between_widows_and_orphans(0, 10, &style, true), 1);
+    }
+
+    /// The page and line count of each fragment of a paragraph of 20pt lines
+    /// that starts `y_offset` down a 100pt page
+    fn text_fragments(y_offset: Pt, line_count: usize) -> Vec<(usize, usize)> {
+        let stylesheet = ResolvedStylesheet::default();
+        let node = DomNode::Text(TextNode::with_children(vec![], &[]));
+        let node_lookup =
+            NodeLookup::from_root_node(&node, &stylesheet, &Default::default(), None).unwrap();
+        let layout_engine = YogaLayout::new(&node_lookup);
+        let paragraph_layout = ParagraphLayout::new();
+
+        let mut paginated_layout_engine = PaginatedLayoutEngine {
+            node_avoids_page_break: HashMap::new(),
+            clip_nodes: HashMap::new(),
+            node_lookup: &node_lookup,
+            paginated_nodes: vec![],
+            paragraph_layout: &paragraph_layout,
+            layout_engine: &layout_engine,
+            stylesheet: &stylesheet,
+            page_height: Pt(100.),
+            debug_cursors: vec![],
+        };
+
+        let line = RenderedTextLine {
+            rich_text: RichText(vec![]),
+            line_metrics: LineMetrics {
+                ascent: Pt(16.),
+                descent: Pt(4.),
+                baseline: Pt(16.),
+                height: Pt(20.),
+                width: Pt(50.),
+                left: Pt(0.),
+            },
+            word_spacing: Pt(0.),
+            runs: vec![],
+        };
+        let text_node = DrawableTextNode {
+            text_block: RenderedTextBlock {
+                lines: vec![line; line_count],
+            },
+            style: Default::default(),
+        };
+
+        let mut node_layout = NodeLayout {
+            top: y_offset,
+            height: Pt(20. * line_count as f64),
+            ..Default::default()
+        };
+        let mut draw_cursor = DrawCursor {
+            y_offset,
+            page_index: 0,
+            page_break_debt: Pt(0.),
+        };
+
+        paginated_layout_engine
+            .draw_text_node(
+                &mut draw_cursor,
+                &mut Style::Unmergeable::default(),
+                &mut node_layout,
+                &text_node,
+                &node,
+            )
+            .unwrap();
+
+        paginated_layout_engine
+            .paginated_nodes
+            .iter()
+            .map(|fragment| match &fragment.drawable_node {
+                DrawableNode::Text(text) => (fragment.page_index, text.text_block.lines.len()),
+                DrawableNode::Container(_) => panic!("Only text was drawn"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn breaks_text_across_pages() {
+        assert_eq!(text_fragments(Pt(10.), 6), vec![(0, 4), (1, 2)]);
+        // A single line would be orphaned, so the whole paragraph moves
+        // without leaving an empty fragment behind
+        assert_eq!(text_fragments(Pt(70.), 4), vec![(1, 4)]);
+    }
 }
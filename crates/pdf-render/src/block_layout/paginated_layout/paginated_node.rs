@@ -1,5 +1,8 @@
 use crate::{
-    block_layout::layout_engine::NodeLayout, paragraph_layout::RenderedTextBlock, stylesheet::Style,
+    block_layout::layout_engine::NodeLayout,
+    doc_structure::NodeId,
+    paragraph_layout::RenderedTextBlock,
+    stylesheet::{BorderRadiusStyle, EdgeStyle, Style},
 };
 
 #[derive(Clone, Debug)]
@@ -13,11 +16,56 @@ pub struct DrawableContainerNode {
     pub style: Style::Unmergeable,
 }
 
+/// A group that the nodes within it are drawn in, which is opened before the
+/// first of them and closed after the last
+#[derive(Clone, Debug)]
+pub enum NodeGroup {
+    Opacity(OpacityGroup),
+    Clip(ClipGroup),
+}
+
+/// A node, with an `opacity` below 1, whose subtree is faded as a whole
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpacityGroup {
+    pub node_id: NodeId,
+    pub opacity: f64,
+}
+
+/// A node with `overflow: hidden`, whose descendants are clipped to its
+/// padding box
+#[derive(Clone, Debug)]
+pub struct ClipGroup {
+    pub node_id: NodeId,
+    /// Where the node is on the page the group is opened on
+    pub fragment: NodeLayout,
+    /// Whether the node starts on the page rather than carrying on from an
+    /// earlier one
+    pub starts_on_page: bool,
+    /// Whether the node ends on the page rather than carrying on to a later
+    /// one
+    pub ends_on_page: bool,
+    pub border_width: EdgeStyle::Unmergeable,
+    pub border_radius: BorderRadiusStyle::Unmergeable,
+}
+
+impl NodeGroup {
+    /// Whether both are the same group of the same node
+    pub fn is_same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Opacity(group), Self::Opacity(other)) => group == other,
+            (Self::Clip(group), Self::Clip(other)) => group.node_id == other.node_id,
+            _ => false,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct PaginatedNode {
     pub page_layout: NodeLayout,
     pub page_index: usize,
     pub drawable_node: DrawableNode,
+    /// The groups the node is drawn within, outermost first
+    pub groups: Vec<NodeGroup>,
 }
 
 #[derive(Clone, Debug)]
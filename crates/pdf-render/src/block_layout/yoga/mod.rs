@@ -9,9 +9,9 @@ use crate::{
     error::DocumentGenerationError,
     paragraph_layout::{ParagraphLayout, ParagraphStyle},
     rich_text::dom_node_conversion::dom_node_to_rich_text,
-    stylesheet::Stylesheet,
+    stylesheet::{ResolvedStylesheet, TextFit},
     utils::node_lookup::NodeLookup,
-    values::Pt,
+    values::{Pt, Size as PtSize},
 };
 
 use self::node_context::NodeContext;
@@ -42,8 +42,8 @@ extern "C" fn measure_func(
     node_ref: NodeRef,
     width: f32,
     _width_measure_mode: MeasureMode,
-    _height: f32,
-    _height_measure_mode: MeasureMode,
+    height: f32,
+    height_measure_mode: MeasureMode,
 ) -> Size {
     let context = yoga::Node::get_context_mut(&node_ref)
         .unwrap()
@@ -54,11 +54,26 @@ extern "C" fn measure_func(
     // content width
     let content_width = Pt(width as f64);
 
-    let text_block = context.paragraph_layout.calculate_layout(
-        ParagraphStyle::default(),
-        &context.rich_text,
-        content_width,
-    );
+    // Text only has a height to shrink into once the node (or its parent)
+    // constrains it
+    let text_block = match (context.fit, height_measure_mode) {
+        (TextFit::Shrink, MeasureMode::Exactly | MeasureMode::AtMost) => {
+            context.paragraph_layout.shrink_to_fit(
+                context.paragraph_style,
+                &context.rich_text,
+                PtSize {
+                    width: content_width,
+                    height: Pt(height as f64),
+                },
+                context.min_font_size,
+            )
+        }
+        _ => context.paragraph_layout.calculate_layout(
+            context.paragraph_style,
+            &context.rich_text,
+            content_width,
+        ),
+    };
 
     match text_block {
         Ok(text_block) => {
@@ -115,19 +130,27 @@ impl<'a> LayoutEngine for YogaLayout<'a> {
         &mut self,
         page_width: Pt,
         root_node: &DomNode,
-        stylesheet: &Stylesheet,
+        stylesheet: &ResolvedStylesheet,
         paragraph_layout: Rc<ParagraphLayout>,
     ) -> Result<(), DocumentGenerationError> {
         for (node, parent) in root_node.block_iter() {
-            let node_style = stylesheet.get_style(Default::default(), node.styles())?;
+            let node_style = self.node_lookup.get_style(node);
 
             let mut layout_node = yoga::Node::from(node_style.clone());
 
             if let DomNode::Text(text_node) = node {
-                let rich_text = dom_node_to_rich_text(text_node, self.node_lookup, stylesheet)?;
+                let rich_text = dom_node_to_rich_text(
+                    text_node,
+                    self.node_lookup,
+                    stylesheet,
+                    &paragraph_layout,
+                )?;
 
                 let context = yoga::Context::new(NodeContext {
                     rich_text,
+                    paragraph_style: ParagraphStyle::from(node_style),
+                    fit: node_style.fit,
+                    min_font_size: node_style.min_font_size,
                     paragraph_layout: paragraph_layout.clone(),
                     text_block: None,
                     calculate_error: None,
@@ -135,6 +158,17 @@ impl<'a> LayoutEngine for YogaLayout<'a> {
 
                 layout_node.set_context(Some(context));
                 layout_node.set_measure_func(Some(measure_func));
+
+                // Text can only shrink to fit a box of a given size, which is
+                // the only time the layout uses `width` and `height`
+                if node_style.fit == TextFit::Shrink {
+                    if let Some(width) = node_style.fixed_width()? {
+                        layout_node.set_width(width.into());
+                    }
+                    if let Some(height) = node_style.fixed_height()? {
+                        layout_node.set_height(height.into());
+                    }
+                }
             }
 
             if let Some(parent) = parent {
@@ -176,3 +210,92 @@ fn check_node_for_error(node: &yoga::Node) -> Result<(), DocumentGenerationError
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        doc_structure::{FontFamilyInfo, StyledNode, TextChild, TextNode},
+        load_fonts_from_doc_structure,
+        stylesheet::{LengthContext, Stylesheet},
+    };
+
+    use super::*;
+
+    fn layout_text(fit: &str, height: &str) -> Result<NodeLayout, DocumentGenerationError> {
+        let fonts: Vec<FontFamilyInfo> = serde_json::from_value(json!([{
+            "familyName": "Inter",
+            "fonts": [{
+                "source": concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../../assets/fonts/inter-static/Inter-Regular.ttf"
+                ),
+                "weight": "Regular",
+            }],
+        }]))
+        .unwrap();
+        let font_collection = load_fonts_from_doc_structure(&fonts).unwrap();
+
+        let mut paragraph_layout = ParagraphLayout::new();
+        paragraph_layout.load_fonts(&font_collection).unwrap();
+
+        let stylesheet = serde_json::from_value::<Stylesheet>(json!({
+            "row": { "flex": { "direction": "Row" } },
+            "text": {
+                "height": height,
+                "fit": fit,
+                "font": { "family": "Inter", "size": "40pt" },
+            },
+        }))
+        .unwrap()
+        .resolve()
+        .unwrap();
+
+        let text_node = DomNode::Text(TextNode::with_children(
+            vec![TextChild::Content("Shrink me".to_owned())],
+            &["text"],
+        ));
+        let text_node_id = text_node.node_id();
+        let root_node = DomNode::Styled(StyledNode::with_children(vec![text_node], &["row"]));
+
+        let page_size = PtSize {
+            width: Pt(400.),
+            height: Pt(400.),
+        };
+        let node_lookup = NodeLookup::from_root_node(
+            &root_node,
+            &stylesheet,
+            &LengthContext::new(page_size.clone(), 72.),
+            None,
+        )
+        .unwrap();
+
+        let mut layout_engine = YogaLayout::new(&node_lookup);
+        layout_engine.build_node_layout(
+            page_size.width,
+            &root_node,
+            &stylesheet,
+            Rc::new(paragraph_layout),
+        )?;
+
+        Ok(layout_engine.get_node_layout(text_node_id))
+    }
+
+    #[test]
+    fn shrinks_text_to_a_fixed_height() {
+        let unshrunk = layout_text("None", "20pt").unwrap();
+        let shrunk = layout_text("Shrink", "20pt").unwrap();
+
+        // A single line at 40pt is too tall for the box, so the text has to
+        // be set smaller, which makes it narrower as well
+        assert!(unshrunk.height > Pt(20.));
+        assert_eq!(shrunk.height, Pt(20.));
+        assert!(shrunk.width < unshrunk.width);
+    }
+
+    #[test]
+    fn reports_malformed_heights() {
+        assert!(layout_text("Shrink", "tall").is_err());
+    }
+}
@@ -2,12 +2,17 @@ use std::rc::Rc;
 
 use crate::{
     error::DocumentGenerationError,
-    paragraph_layout::{ParagraphLayout, RenderedTextBlock},
+    paragraph_layout::{ParagraphLayout, ParagraphStyle, RenderedTextBlock},
     rich_text::RichText,
+    stylesheet::TextFit,
+    values::Pt,
 };
 
 pub(super) struct NodeContext {
     pub rich_text: RichText,
+    pub paragraph_style: ParagraphStyle,
+    pub fit: TextFit,
+    pub min_font_size: Pt,
     pub paragraph_layout: Rc<ParagraphLayout>,
     pub text_block: Option<RenderedTextBlock>,
     pub calculate_error: Option<DocumentGenerationError>,
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
@@ -7,7 +8,7 @@ use super::{ImageNode, NodeId, StyledNode, TextNode};
 
 pub use super::HasNodeId;
 
-#[derive(TS, Clone, Deserialize, Debug)]
+#[derive(TS, JsonSchema, Clone, Deserialize, Debug)]
 #[serde(tag = "type")]
 #[ts(export)]
 pub enum DomNode {
@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
 use crate::fonts::FontAttributes;
 
-#[derive(TS, Debug, Deserialize)]
+#[derive(TS, JsonSchema, Debug, Deserialize)]
 #[ts(export, rename_all = "camelCase")]
 pub struct FontInfo {
     pub source: String,
@@ -12,7 +13,7 @@ pub struct FontInfo {
     pub attributes: FontAttributes,
 }
 
-#[derive(TS, Debug, Deserialize)]
+#[derive(TS, JsonSchema, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, rename_all = "camelCase")]
 pub struct FontFamilyInfo {
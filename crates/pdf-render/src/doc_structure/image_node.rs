@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
 use super::{has_node_id::HasNodeId, NodeId};
 
-#[derive(TS, Clone, Deserialize, Debug)]
+#[derive(TS, JsonSchema, Clone, Deserialize, Debug)]
 #[ts(export)]
 pub struct ImageNode {
     #[serde(skip)]
@@ -1,29 +1,35 @@
 //! This is the raw representation of the DOM that will ultimately
 //!  be used throughout the rest of the engine. This includes the stylesheet
 //!  definition, the dom hierarchy, and the font definitions.
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
 use serde::Deserialize;
 
 mod dom_node;
 mod fonts;
 mod has_node_id;
 mod image_node;
+mod output_intent;
 mod styled_node;
+mod stylesheet_import;
 mod text_node;
 
 pub use dom_node::DomNode;
 pub use fonts::FontFamilyInfo;
 pub use has_node_id::HasNodeId;
 pub use image_node::ImageNode;
+pub use output_intent::OutputIntentInfo;
 pub use styled_node::StyledNode;
+pub use stylesheet_import::StylesheetImport;
 pub use text_node::{TextChild, TextNode};
 use ts_rs::TS;
 
 use crate::{
     stylesheet::{EdgeStyle, Stylesheet},
     utils::unique_id::create_id,
+    values::DEFAULT_DPI,
 };
 
-#[derive(TS, Debug, Deserialize)]
+#[derive(TS, JsonSchema, Debug, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 #[ts(export, rename_all = "camelCase")]
 pub struct DocStructure {
@@ -31,12 +37,35 @@ pub struct DocStructure {
     pub document_title: String,
     pub page_size: String,
     #[serde(default)]
-    #[ts(type = "EdgeStyle")]
     pub page_margins: EdgeStyle::Unmergeable,
+    /// Pixels per inch, for converting `px` lengths in the stylesheet
+    #[serde(default = "default_dpi")]
+    pub dpi: f64,
+    /// The language of the text as a BCP 47 tag (i.e. `en-US`), which decides
+    /// how words are hyphenated
+    #[serde(default)]
+    pub lang: Option<String>,
     pub fonts: Vec<FontFamilyInfo>,
-    #[ts(type = "Record<string, Style>")]
+    /// Embeds an ICC profile for the printer, which print vendors that want
+    /// CMYK often require
+    #[serde(default)]
+    pub output_intent: Option<OutputIntentInfo>,
+    /// Merged, in order, before the inline `stylesheet`
+    #[serde(default)]
+    pub stylesheet_imports: Vec<StylesheetImport>,
+    #[ts(type = "Record<string, Style> & { variables?: Record<string, string | number> }")]
     pub stylesheet: Stylesheet,
     pub root: DomNode,
 }
 
+fn default_dpi() -> f64 {
+    DEFAULT_DPI
+}
+
 create_id!(NodeId);
+
+/// The JSON Schema describing a document, for validating documents before
+/// they're sent to be rendered.
+pub fn document_schema() -> RootSchema {
+    schema_for!(DocStructure)
+}
@@ -0,0 +1,18 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ts_rs::TS;
+
+/// The printing condition the document's colors are meant for, which print
+/// vendors check process colors against
+#[derive(TS, JsonSchema, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[ts(export, rename_all = "camelCase")]
+pub struct OutputIntentInfo {
+    /// The path of the condition's ICC profile, within the profile directory
+    /// the renderer is set up with
+    pub source: String,
+    /// The name of the condition in the ICC's registry, e.g. `FOGRA39`
+    pub identifier: String,
+    #[serde(default)]
+    pub info: Option<String>,
+}
@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
 use super::{has_node_id::HasNodeId, DomNode, NodeId};
 
-#[derive(TS, Clone, Default, Deserialize, Debug)]
+#[derive(TS, JsonSchema, Clone, Default, Deserialize, Debug)]
 #[ts(export)]
 pub struct StyledNode {
     #[serde(skip)]
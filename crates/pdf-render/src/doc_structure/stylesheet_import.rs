@@ -0,0 +1,15 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ts_rs::TS;
+
+/// A stylesheet that is merged underneath the document's inline stylesheet.
+#[derive(TS, JsonSchema, Clone, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type")]
+#[ts(export)]
+pub enum StylesheetImport {
+    /// A JSON stylesheet on disk, within the directory the
+    /// `StylesheetRegistry` allows file imports from (the server allows none)
+    File { path: String },
+    /// A stylesheet registered with the `StylesheetRegistry` ahead of time
+    Registered { name: String },
+}
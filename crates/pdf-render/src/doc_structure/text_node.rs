@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
@@ -5,17 +6,21 @@ use crate::utils::tree_iter::{TreeIterator, TreeNode};
 
 use super::{has_node_id::HasNodeId, NodeId};
 
-#[derive(TS, Default, Deserialize, Debug, Clone)]
+#[derive(TS, JsonSchema, Default, Deserialize, Debug, Clone)]
 #[ts(export)]
 pub struct TextNode {
     #[serde(skip)]
     pub node_id: NodeId,
     #[serde(default)]
     pub styles: Vec<String>,
+    /// The language of the text as a BCP 47 tag (i.e. `en-US`), overriding
+    /// the document's
+    #[serde(default)]
+    pub lang: Option<String>,
     pub children: Vec<TextChild>,
 }
 
-#[derive(TS, Deserialize, Debug, Clone)]
+#[derive(TS, JsonSchema, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 #[ts(export)]
 pub enum TextChild {
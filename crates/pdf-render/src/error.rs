@@ -27,6 +27,15 @@ pub enum InternalServerError {
 
     #[error("Font face not loaded into text layout engine: {family_name}")]
     FontFamilyNotRegisteredForLayoutEngine { family_name: String },
+
+    #[error("Error finishing the written PDF")]
+    FinishPdfError { source: Box<dyn std::error::Error> },
+
+    #[error("Error loading registered stylesheet: {path}")]
+    LoadStylesheetError {
+        source: Box<dyn std::error::Error>,
+        path: String,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -55,9 +64,45 @@ pub enum UserInputError {
         source: color_processing::ParseError,
     },
 
+    #[error("Unable to parse color, {color}: {reason}")]
+    MalformedColor { color: String, reason: String },
+
+    #[error("Unable to parse gradient, {gradient}: {reason}")]
+    MalformedGradient { gradient: String, reason: String },
+
     #[error("Style name does not exist on stylesheet: {style_name}")]
     StyleDoesNotExist { style_name: String },
 
+    #[error("Unable to parse style, {style_name}: {source}")]
+    MalformedStyle {
+        style_name: String,
+        source: serde_json::Error,
+    },
+
+    #[error("Stylesheet variable is not defined: {variable_name}")]
+    UndefinedStyleVariable { variable_name: String },
+
+    #[error("Stylesheet variables reference each other in a cycle: {cycle}")]
+    CyclicStyleVariable { cycle: String },
+
+    #[error("Styles extend each other in a cycle: {cycle}")]
+    CyclicStyleExtends { cycle: String },
+
+    #[error("Unable to import stylesheet, {path}: {source}")]
+    StylesheetImportError {
+        source: Box<dyn std::error::Error>,
+        path: String,
+    },
+
+    #[error("Stylesheet files can't be imported, import a registered stylesheet: {path}")]
+    StylesheetFileImportsDisabled { path: String },
+
+    #[error("Stylesheet file is not within the stylesheet directory: {path}")]
+    StylesheetFileNotFound { path: String },
+
+    #[error("Stylesheet has not been registered: {name}")]
+    StylesheetNotRegistered { name: String },
+
     #[error("Unable to parse unit string in stylesheet: {source_str}")]
     MalformedUnitString { source_str: String },
 
@@ -66,6 +111,36 @@ pub enum UserInputError {
 
     #[error("Unit does is not supported: {attached_unit}")]
     UnsupportedUnit { attached_unit: String },
+
+    #[error("Relative lengths can only be used within styles: {length}")]
+    ContextDependentLength { length: String },
+
+    #[error("Unable to read output intent profile, {path}: {source}")]
+    OutputProfileReadError {
+        source: std::io::Error,
+        path: String,
+    },
+
+    #[error("Output intent profiles can't be embedded, no profile directory is set: {path}")]
+    OutputProfilesDisabled { path: String },
+
+    #[error("Output intent profile is not within the profile directory: {path}")]
+    OutputProfileNotFound { path: String },
+
+    #[error("Output intent profile is not a CMYK or gray ICC profile: {path}")]
+    UnsupportedOutputProfile { path: String },
+
+    #[error("Unable to read image, {path}: {source}")]
+    ImageReadError {
+        source: std::io::Error,
+        path: String,
+    },
+
+    #[error("Unable to decode image, {path}: {source}")]
+    ImageDecodeError {
+        source: image::ImageError,
+        path: String,
+    },
 }
 
 #[derive(Error, Debug)]
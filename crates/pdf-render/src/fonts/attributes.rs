@@ -1,8 +1,9 @@
 use num_derive::FromPrimitive;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
-#[derive(TS, Deserialize, Default, Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[derive(TS, JsonSchema, Deserialize, Default, Debug, Copy, Clone, Hash, PartialEq, Eq)]
 #[ts(export, rename_all = "camelCase")]
 pub struct FontAttributes {
     #[serde(default)]
@@ -27,7 +28,7 @@ impl FontAttributes {
     }
 }
 
-#[derive(TS, Hash, Eq, PartialEq, Debug, Clone, Copy, FromPrimitive, Deserialize)]
+#[derive(TS, JsonSchema, Hash, Eq, PartialEq, Debug, Clone, Copy, FromPrimitive, Deserialize)]
 #[ts(export)]
 pub enum FontSlant {
     Normal,
@@ -49,7 +50,7 @@ impl From<&str> for FontSlant {
     }
 }
 
-#[derive(TS, Hash, Eq, PartialEq, Debug, Clone, Copy, FromPrimitive, Deserialize)]
+#[derive(TS, JsonSchema, Hash, Eq, PartialEq, Debug, Clone, Copy, FromPrimitive, Deserialize)]
 #[ts(export)]
 pub enum FontWeight {
     Thin = 100,
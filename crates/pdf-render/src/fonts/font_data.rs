@@ -1,12 +1,13 @@
 use bytes::Bytes;
 
-use super::{FontAttributes, FontId};
+use super::{FontAttributes, FontId, SmallCaps};
 
 pub struct FontData {
     id: FontId,
     family_name: String,
     attributes: FontAttributes,
     data: Bytes,
+    small_caps: Option<SmallCaps>,
 }
 
 impl FontData {
@@ -18,6 +19,7 @@ impl FontData {
     ) -> Self {
         Self {
             id,
+            small_caps: SmallCaps::from_font(data.clone()),
             data,
             family_name,
             attributes,
@@ -39,4 +41,9 @@ impl FontData {
     pub fn font_id(&self) -> FontId {
         self.id
     }
+
+    /// The font's own small capitals, if it has them
+    pub fn small_caps(&self) -> Option<&SmallCaps> {
+        self.small_caps.as_ref()
+    }
 }
@@ -6,9 +6,11 @@ mod font_collection;
 mod font_data;
 mod font_family_collection;
 mod font_id;
+mod small_caps;
 
 pub use attributes::{FontAttributes, FontSlant, FontWeight};
 pub use font_collection::FontCollection;
 pub use font_data::FontData;
 pub use font_family_collection::FontFamilyCollection;
 pub use font_id::FontId;
+pub use small_caps::SmallCaps;
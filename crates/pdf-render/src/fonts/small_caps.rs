@@ -0,0 +1,392 @@
+//! Reads the small capitals a font provides through its `smcp` feature. Skia
+//!  lays them out by turning the feature on, but they have no codepoint of
+//!  their own, so the PDF writer draws them by glyph id.
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+/// The `GSUB` lookup that replaces one glyph with another
+const SINGLE_SUBSTITUTION: u16 = 1;
+/// The `GSUB` lookup that points to a subtable of another type
+const EXTENSION_SUBSTITUTION: u16 = 7;
+
+pub struct SmallCaps {
+    font: Bytes,
+    /// The small capital of every glyph that has one
+    substitutions: HashMap<u16, u16>,
+}
+
+impl SmallCaps {
+    /// `None` when the font has no `smcp` feature that replaces single
+    /// glyphs, or can't be read
+    pub fn from_font(font: Bytes) -> Option<Self> {
+        let gsub = table(&font, b"GSUB")?;
+        let substitutions = small_caps_substitutions(gsub)?;
+
+        if substitutions.is_empty() {
+            None
+        } else {
+            Some(Self {
+                font,
+                substitutions,
+            })
+        }
+    }
+
+    /// The glyph drawn for each character of `text`, with small capitals in
+    /// place of the letters that have them, and its advance in thousandths of
+    /// an em
+    pub fn glyphs(&self, text: &str) -> Vec<(u16, f64)> {
+        let cmap = table(&self.font, b"cmap").and_then(character_map);
+        let advances = Advances::from_font(&self.font);
+
+        text.chars()
+            .map(|char| {
+                let glyph = cmap.as_ref().and_then(|cmap| cmap.glyph(char)).unwrap_or(0);
+                let glyph = self.substitutions.get(&glyph).copied().unwrap_or(glyph);
+                let advance = advances.as_ref().map_or(0., |advances| advances.of(glyph));
+
+                (glyph, advance)
+            })
+            .collect()
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// The table of the font tagged `tag`
+fn table<'a>(font: &'a [u8], tag: &[u8; 4]) -> Option<&'a [u8]> {
+    let table_count = read_u16(font, 4)? as usize;
+
+    (0..table_count)
+        .map(|index| 12 + index * 16)
+        .find(|&record| font.get(record..record + 4) == Some(&tag[..]))
+        .and_then(|record| {
+            let offset = read_u32(font, record + 8)? as usize;
+            let length = read_u32(font, record + 12)? as usize;
+
+            font.get(offset..offset + length)
+        })
+}
+
+/// Every substitution made by the single substitution lookups of the `smcp`
+/// features, for any script
+fn small_caps_substitutions(gsub: &[u8]) -> Option<HashMap<u16, u16>> {
+    let feature_list = gsub.get(read_u16(gsub, 6)? as usize..)?;
+    let lookup_list = gsub.get(read_u16(gsub, 8)? as usize..)?;
+
+    let mut substitutions = HashMap::new();
+
+    for index in 0..read_u16(feature_list, 0)? as usize {
+        let record = 2 + index * 6;
+        if feature_list.get(record..record + 4) != Some(&b"smcp"[..]) {
+            continue;
+        }
+
+        let feature = feature_list.get(read_u16(feature_list, record + 4)? as usize..)?;
+
+        for index in 0..read_u16(feature, 2)? as usize {
+            let lookup_index = read_u16(feature, 4 + index * 2)? as usize;
+            let lookup =
+                lookup_list.get(read_u16(lookup_list, 2 + lookup_index * 2)? as usize..)?;
+
+            let lookup_type = read_u16(lookup, 0)?;
+            for index in 0..read_u16(lookup, 4)? as usize {
+                let subtable = lookup.get(read_u16(lookup, 6 + index * 2)? as usize..)?;
+
+                match lookup_type {
+                    SINGLE_SUBSTITUTION => single_substitutions(subtable, &mut substitutions)?,
+                    EXTENSION_SUBSTITUTION if read_u16(subtable, 2)? == SINGLE_SUBSTITUTION => {
+                        let subtable = subtable.get(read_u32(subtable, 4)? as usize..)?;
+
+                        single_substitutions(subtable, &mut substitutions)?
+                    }
+                    // Small capitals that take more than one glyph, or depend
+                    // on the glyphs around them, aren't drawn
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Some(substitutions)
+}
+
+fn single_substitutions(subtable: &[u8], substitutions: &mut HashMap<u16, u16>) -> Option<()> {
+    let coverage = subtable.get(read_u16(subtable, 2)? as usize..)?;
+
+    for (coverage_index, glyph) in covered_glyphs(coverage)?.into_iter().enumerate() {
+        let substitute = match read_u16(subtable, 0)? {
+            // The same delta for every glyph
+            1 => glyph.wrapping_add(read_u16(subtable, 4)?),
+            2 => read_u16(subtable, 6 + coverage_index * 2)?,
+            _ => return None,
+        };
+
+        substitutions.insert(glyph, substitute);
+    }
+
+    Some(())
+}
+
+/// The glyphs of a coverage table, in the order of their coverage index
+fn covered_glyphs(coverage: &[u8]) -> Option<Vec<u16>> {
+    let count = read_u16(coverage, 2)? as usize;
+
+    match read_u16(coverage, 0)? {
+        1 => (0..count)
+            .map(|index| read_u16(coverage, 4 + index * 2))
+            .collect(),
+        2 => {
+            let mut glyphs = vec![];
+
+            for index in 0..count {
+                let record = 4 + index * 6;
+                let start = read_u16(coverage, record)?;
+                let end = read_u16(coverage, record + 2)?;
+
+                glyphs.extend(start..=end);
+            }
+
+            Some(glyphs)
+        }
+        _ => None,
+    }
+}
+
+/// A subtable of the `cmap` table that maps Unicode characters to glyphs
+enum CharacterMap<'a> {
+    /// Segments of the basic multilingual plane
+    SegmentMapping(&'a [u8]),
+    /// Groups of characters from any plane
+    SegmentedCoverage(&'a [u8]),
+}
+
+/// The subtable covering the most characters
+fn character_map(cmap: &[u8]) -> Option<CharacterMap<'_>> {
+    let subtables: Vec<&[u8]> = (0..read_u16(cmap, 2)? as usize)
+        .filter_map(|index| {
+            let record = 4 + index * 8;
+            let platform = read_u16(cmap, record)?;
+            let encoding = read_u16(cmap, record + 2)?;
+            let is_unicode = platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10));
+
+            if is_unicode {
+                cmap.get(read_u32(cmap, record + 4)? as usize..)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let with_format = |format| {
+        subtables
+            .iter()
+            .find(|subtable| read_u16(subtable, 0) == Some(format))
+            .copied()
+    };
+
+    with_format(12)
+        .map(CharacterMap::SegmentedCoverage)
+        .or_else(|| with_format(4).map(CharacterMap::SegmentMapping))
+}
+
+impl CharacterMap<'_> {
+    fn glyph(&self, char: char) -> Option<u16> {
+        let code = char as u32;
+
+        match *self {
+            CharacterMap::SegmentMapping(subtable) => {
+                if code > 0xFFFF {
+                    return None;
+                }
+                let code = code as u16;
+
+                let segment_count = read_u16(subtable, 6)? as usize / 2;
+                let end_codes = 14;
+                let start_codes = end_codes + segment_count * 2 + 2;
+                let deltas = start_codes + segment_count * 2;
+                let range_offsets = deltas + segment_count * 2;
+
+                let segment = (0..segment_count)
+                    .find(|&segment| read_u16(subtable, end_codes + segment * 2) >= Some(code))?;
+                let start = read_u16(subtable, start_codes + segment * 2)?;
+                if start > code {
+                    return None;
+                }
+
+                let delta = read_u16(subtable, deltas + segment * 2)?;
+                let range_offset = read_u16(subtable, range_offsets + segment * 2)? as usize;
+
+                if range_offset == 0 {
+                    Some(code.wrapping_add(delta))
+                } else {
+                    // The offset is from where it's stored to the glyph
+                    let offset =
+                        range_offsets + segment * 2 + range_offset + (code - start) as usize * 2;
+
+                    match read_u16(subtable, offset)? {
+                        0 => None,
+                        glyph => Some(glyph.wrapping_add(delta)),
+                    }
+                }
+            }
+            CharacterMap::SegmentedCoverage(subtable) => (0..read_u32(subtable, 12)? as usize)
+                .find_map(|index| {
+                    let group = 16 + index * 12;
+                    let start = read_u32(subtable, group)?;
+                    let end = read_u32(subtable, group + 4)?;
+
+                    if (start..=end).contains(&code) {
+                        Some((read_u32(subtable, group + 8)? + code - start) as u16)
+                    } else {
+                        None
+                    }
+                }),
+        }
+    }
+}
+
+/// The horizontal advance of every glyph
+struct Advances<'a> {
+    metrics: &'a [u8],
+    metric_count: usize,
+    units_per_em: f64,
+}
+
+impl<'a> Advances<'a> {
+    fn from_font(font: &'a [u8]) -> Option<Self> {
+        let units_per_em = read_u16(table(font, b"head")?, 18)?;
+        let metric_count = read_u16(table(font, b"hhea")?, 34)? as usize;
+
+        if units_per_em == 0 || metric_count == 0 {
+            return None;
+        }
+
+        Some(Self {
+            metrics: table(font, b"hmtx")?,
+            metric_count,
+            units_per_em: units_per_em as f64,
+        })
+    }
+
+    /// In thousandths of an em
+    fn of(&self, glyph: u16) -> f64 {
+        // Glyphs past the last metric share its advance
+        let metric = (glyph as usize).min(self.metric_count - 1);
+
+        read_u16(self.metrics, metric * 4)
+            .map_or(0., |advance| advance as f64 * 1000. / self.units_per_em)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTER: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../assets/fonts/inter-static/Inter-Regular.ttf"
+    );
+
+    fn u16s(values: &[u16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    }
+
+    /// A font with the glyphs `.notdef`, `a`, `A` and a small capital `a`
+    /// that replaces `a` through `smcp`
+    fn font_with_small_caps() -> Vec<u8> {
+        let mut head = vec![0; 54];
+        head[18..20].copy_from_slice(&2000u16.to_be_bytes());
+
+        let mut hhea = vec![0; 36];
+        hhea[34..36].copy_from_slice(&4u16.to_be_bytes());
+
+        let hmtx = u16s(&[500, 0, 500, 0, 700, 0, 600, 0]);
+
+        // A segment each for `A` and `a`, and the closing one
+        let cmap = [
+            u16s(&[0, 1, 3, 1, 0, 12]),
+            u16s(&[4, 40, 0, 6, 4, 1, 2]),
+            u16s(&[0x41, 0x61, 0xFFFF, 0]),
+            u16s(&[0x41, 0x61, 0xFFFF]),
+            u16s(&[2u16.wrapping_sub(0x41), 1u16.wrapping_sub(0x61), 1]),
+            u16s(&[0, 0, 0]),
+        ]
+        .concat();
+
+        let gsub = [
+            // Header, with an empty script list
+            u16s(&[1, 0, 10, 12, 26]),
+            u16s(&[0]),
+            // Feature list
+            u16s(&[1]),
+            b"smcp".to_vec(),
+            u16s(&[8]),
+            u16s(&[0, 1, 0]),
+            // Lookup list
+            u16s(&[1, 4]),
+            u16s(&[SINGLE_SUBSTITUTION, 0, 1, 8]),
+            u16s(&[2, 8, 1, 3]),
+            // Coverage
+            u16s(&[1, 1, 1]),
+        ]
+        .concat();
+
+        let tables: [(&[u8; 4], Vec<u8>); 5] = [
+            (b"GSUB", gsub),
+            (b"cmap", cmap),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+        ];
+
+        let mut font = [vec![0, 1, 0, 0], u16s(&[tables.len() as u16, 0, 0, 0])].concat();
+        let mut offset = 12 + tables.len() * 16;
+
+        for (tag, data) in tables.iter() {
+            font.extend_from_slice(&tag[..]);
+            font.extend_from_slice(&0u32.to_be_bytes());
+            font.extend_from_slice(&(offset as u32).to_be_bytes());
+            font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in tables.iter() {
+            font.extend_from_slice(data);
+        }
+
+        font
+    }
+
+    #[test]
+    fn draws_the_small_capitals_of_the_font() {
+        let small_caps = SmallCaps::from_font(Bytes::from(font_with_small_caps())).unwrap();
+
+        assert_eq!(
+            small_caps.glyphs("aAb"),
+            vec![(3, 300.), (2, 350.), (0, 250.)]
+        );
+    }
+
+    #[test]
+    fn reads_no_small_capitals_from_fonts_without_them() {
+        let inter = std::fs::read(INTER).unwrap();
+
+        assert!(SmallCaps::from_font(Bytes::from(inter)).is_none());
+        assert!(SmallCaps::from_font(Bytes::from("1")).is_none());
+    }
+}
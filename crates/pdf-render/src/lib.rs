@@ -4,17 +4,18 @@ use block_layout::{
     layout_engine::LayoutEngine, paginated_layout::PaginatedLayoutEngine, yoga::YogaLayout,
 };
 use bytes::Bytes;
-use doc_structure::FontFamilyInfo;
+use doc_structure::{FontFamilyInfo, OutputIntentInfo};
 use document_builder::DocumentBuilder;
 use fonts::{FontCollection, FontFamilyCollection};
 use paragraph_layout::ParagraphLayout;
-use print_pdf_writer::PrintPdfWriter;
+use print_pdf_writer::{OutputIntent, PrintPdfWriter};
+use stylesheet::{LengthContext, StylesheetRegistry};
 use tracing::{info, span, Level};
 
-use std::{io::Write, rc::Rc};
+use std::{io::Write, path::Path, rc::Rc};
 
-use utils::node_lookup::NodeLookup;
-use values::Pt;
+use utils::{contained_path::resolve_contained_path, node_lookup::NodeLookup};
+use values::{Pt, Size};
 
 pub mod block_layout;
 pub mod doc_structure;
@@ -29,7 +30,7 @@ pub mod stylesheet;
 pub mod utils;
 pub mod values;
 
-use error::DocumentGenerationError;
+use error::{DocumentGenerationError, UserInputError};
 
 pub fn load_fonts_from_doc_structure(
     fonts: &[FontFamilyInfo],
@@ -54,8 +55,42 @@ pub fn load_fonts_from_doc_structure(
     Ok(font_collection)
 }
 
+/// The profile's `source` is relative to `profile_directory`, and has to be
+/// within it
+pub fn load_output_intent(
+    output_intent_info: &OutputIntentInfo,
+    profile_directory: Option<&Path>,
+) -> Result<OutputIntent, DocumentGenerationError> {
+    let path = &output_intent_info.source;
+
+    let profile_directory = profile_directory
+        .ok_or_else(|| UserInputError::OutputProfilesDisabled { path: path.clone() })?;
+
+    let full_path = resolve_contained_path(profile_directory, path)
+        .ok_or_else(|| UserInputError::OutputProfileNotFound { path: path.clone() })?;
+
+    let profile =
+        std::fs::read(full_path).map_err(|source| UserInputError::OutputProfileReadError {
+            source,
+            path: path.clone(),
+        })?;
+
+    let output_intent = OutputIntent::new(
+        Bytes::from(profile),
+        output_intent_info.identifier.clone(),
+        output_intent_info.info.clone(),
+    )
+    .ok_or_else(|| UserInputError::UnsupportedOutputProfile { path: path.clone() })?;
+
+    Ok(output_intent)
+}
+
+/// Output intents can only be embedded when there's an
+/// `output_profile_directory` to read their profiles from.
 pub fn build_pdf_from_dom<W: Write>(
     doc_structure: &doc_structure::DocStructure,
+    stylesheet_registry: &StylesheetRegistry,
+    output_profile_directory: Option<&Path>,
     pdf_doc_writer: W,
 ) -> Result<W, DocumentGenerationError> {
     let page_size = if doc_structure.page_size.to_lowercase() == "letter" {
@@ -65,15 +100,37 @@ pub fn build_pdf_from_dom<W: Write>(
     };
 
     let font_collection = load_fonts_from_doc_structure(&doc_structure.fonts)?;
-    let pdf_writer = PrintPdfWriter::new(
+    let mut pdf_writer = PrintPdfWriter::new(
         &doc_structure.document_title,
         page_size.clone(),
         doc_structure.page_margins.clone(),
         &font_collection,
     );
+    pdf_writer.set_dpi(doc_structure.dpi);
+
+    if let Some(output_intent_info) = &doc_structure.output_intent {
+        pdf_writer.set_output_intent(load_output_intent(
+            output_intent_info,
+            output_profile_directory,
+        )?);
+    }
+
+    let stylesheet = &stylesheet_registry.compose(
+        &doc_structure.stylesheet_imports,
+        &doc_structure.stylesheet,
+    )?;
 
-    let stylesheet = &doc_structure.stylesheet;
-    let node_lookup = NodeLookup::from_root_node(&doc_structure.root, stylesheet)?;
+    let page_content_size = Size {
+        width: Pt::from(page_size.width) - doc_structure.page_margins.horizontal(),
+        height: Pt::from(page_size.height) - doc_structure.page_margins.vertical(),
+    };
+
+    let node_lookup = NodeLookup::from_root_node(
+        &doc_structure.root,
+        stylesheet,
+        &LengthContext::new(page_content_size.clone(), doc_structure.dpi),
+        doc_structure.lang.as_deref(),
+    )?;
 
     let mut paragraph_layout = ParagraphLayout::new();
     paragraph_layout.load_fonts(&font_collection)?;
@@ -82,7 +139,7 @@ pub fn build_pdf_from_dom<W: Write>(
 
     let mut layout_engine = YogaLayout::new(&node_lookup);
     layout_engine.build_node_layout(
-        Pt::from(page_size.width) - doc_structure.page_margins.horizontal(),
+        page_content_size.width,
         &doc_structure.root,
         stylesheet,
         paragraph_layout.clone(),
@@ -94,7 +151,7 @@ pub fn build_pdf_from_dom<W: Write>(
         &node_lookup,
         &paragraph_layout,
         stylesheet,
-        Pt::from(page_size.height) - doc_structure.page_margins.vertical(),
+        page_content_size.height,
     )?;
 
     let mut pdf_builder = DocumentBuilder::new(pdf_writer);
@@ -1,38 +1,43 @@
 use skia_safe::textlayout as skia_layout;
 
-pub enum TextAlign {
-    Left,
-    Right,
-    Center,
-}
-
-impl Default for TextAlign {
-    fn default() -> Self {
-        TextAlign::Left
-    }
-}
+use crate::stylesheet::{Style, TextAlign, TextOverflow};
 
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub struct ParagraphStyle {
     pub align: TextAlign,
+    pub max_lines: Option<usize>,
+    pub text_overflow: TextOverflow,
 }
 
 impl ParagraphStyle {
     pub fn left() -> Self {
         Self {
             align: TextAlign::Left,
+            ..Default::default()
         }
     }
 
     pub fn right() -> Self {
         Self {
             align: TextAlign::Right,
+            ..Default::default()
         }
     }
 
     pub fn center() -> Self {
         Self {
             align: TextAlign::Center,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Style::Unmergeable> for ParagraphStyle {
+    fn from(style: &Style::Unmergeable) -> Self {
+        Self {
+            align: style.text_align,
+            max_lines: style.max_lines,
+            text_overflow: style.text_overflow,
         }
     }
 }
@@ -40,9 +45,12 @@ impl ParagraphStyle {
 impl From<TextAlign> for skia_layout::TextAlign {
     fn from(align: TextAlign) -> Self {
         match align {
+            TextAlign::Start => skia_layout::TextAlign::Start,
+            TextAlign::End => skia_layout::TextAlign::End,
             TextAlign::Left => skia_layout::TextAlign::Left,
-            TextAlign::Center => skia_layout::TextAlign::Center,
             TextAlign::Right => skia_layout::TextAlign::Right,
+            TextAlign::Center => skia_layout::TextAlign::Center,
+            TextAlign::Justify => skia_layout::TextAlign::Justify,
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::values::Pt;
+use crate::{rich_text::RichText, values::Pt};
 use skia_safe::textlayout as skia_layout;
 
 #[derive(Clone, Debug)]
@@ -24,3 +24,96 @@ impl<'a> From<&skia_layout::LineMetrics<'a>> for LineMetrics {
         }
     }
 }
+
+/// Where one span of a line was laid out and the decoration metrics of its
+/// font. Positions are measured down from the baseline.
+#[derive(Clone, Debug)]
+pub struct RunMetrics {
+    /// Relative to the left of the paragraph
+    pub left: Pt,
+    /// Excludes trailing whitespace at the end of the line
+    pub width: Pt,
+    pub ascent: Pt,
+    pub descent: Pt,
+    pub underline_position: Pt,
+    pub underline_thickness: Pt,
+    pub strikeout_position: Pt,
+    pub strikeout_thickness: Pt,
+}
+
+impl RunMetrics {
+    /// Measures every span of `line_text`, which is the text of `line_metrics`
+    /// within `paragraph`.
+    pub(super) fn for_line(
+        paragraph: &skia_layout::Paragraph,
+        line_metrics: &skia_layout::LineMetrics,
+        line_text: &RichText,
+    ) -> Vec<Self> {
+        let mut run_start = line_metrics.start_index;
+
+        line_text
+            .0
+            .iter()
+            .map(|span| {
+                let run_end = run_start + span.text.chars().count();
+                let drawn_end = run_end.min(line_metrics.end_excluding_whitespaces);
+
+                let rects = if run_start < drawn_end {
+                    paragraph.get_rects_for_range(
+                        run_start..drawn_end,
+                        skia_layout::RectHeightStyle::Tight,
+                        skia_layout::RectWidthStyle::Tight,
+                    )
+                } else {
+                    vec![]
+                };
+
+                let left = rects
+                    .iter()
+                    .map(|text_box| text_box.rect.left)
+                    .reduce(f32::min)
+                    .unwrap_or(0.);
+                let right = rects
+                    .iter()
+                    .map(|text_box| text_box.rect.right)
+                    .reduce(f32::max)
+                    .unwrap_or(left);
+
+                let font_metrics = line_metrics
+                    .get_style_metrics(run_start..run_end)
+                    .first()
+                    .map(|(_, style_metrics)| style_metrics.font_metrics)
+                    .unwrap_or_default();
+
+                run_start = run_end;
+
+                // Roughly what most fonts recommend, for those that don't say
+                let em = font_metrics.descent - font_metrics.ascent;
+                let thickness = em / 16.;
+
+                Self {
+                    left: f64::from(left).into(),
+                    width: f64::from(right - left).into(),
+                    ascent: f64::from(-font_metrics.ascent).into(),
+                    descent: f64::from(font_metrics.descent).into(),
+                    underline_position: f64::from(
+                        font_metrics.underline_position().unwrap_or(em / 10.),
+                    )
+                    .into(),
+                    underline_thickness: f64::from(
+                        font_metrics.underline_thickness().unwrap_or(thickness),
+                    )
+                    .into(),
+                    strikeout_position: f64::from(
+                        font_metrics.strikeout_position().unwrap_or(em / -4.),
+                    )
+                    .into(),
+                    strikeout_thickness: f64::from(
+                        font_metrics.strikeout_thickness().unwrap_or(thickness),
+                    )
+                    .into(),
+                }
+            })
+            .collect()
+    }
+}
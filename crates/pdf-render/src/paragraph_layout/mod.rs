@@ -9,8 +9,8 @@ mod line_metrics;
 mod styles;
 mod text_block;
 
-pub use layout_style::{ParagraphStyle, TextAlign};
-pub use line_metrics::LineMetrics;
+pub use layout_style::ParagraphStyle;
+pub use line_metrics::{LineMetrics, RunMetrics};
 pub use text_block::{RenderedTextBlock, RenderedTextLine};
 
 use skia_layout::{ParagraphBuilder, TypefaceFontProvider};
@@ -19,14 +19,29 @@ use skia_safe::{Data, FontMgr, Typeface};
 
 use crate::{
     error::{DocumentGenerationError, InternalServerError},
-    fonts::FontCollection,
-    rich_text::RichText,
-    values::Pt,
+    fonts::{FontAttributes, FontCollection},
+    rich_text::{
+        hyphenation::{resolve_soft_hyphens, SOFT_HYPHEN},
+        span_background::SPACER,
+        RichText, RichTextSpan,
+    },
+    stylesheet::{TextAlign, TextOverflow},
+    values::{Pt, Size},
 };
 
+use self::text_block::whitespace_patches;
+
+/// Drawn in place of the text past `max_lines`
+const ELLIPSIS: &str = "…";
+
+/// How many times `shrink_to_fit` halves the range of font sizes it searches
+const SHRINK_STEPS: usize = 8;
+
 pub struct ParagraphLayout {
     skia_font_collection: skia_layout::FontCollection,
     font_families: HashSet<String>,
+    /// The fonts with their own small capitals
+    small_caps_fonts: HashSet<(String, FontAttributes)>,
 }
 
 impl Default for ParagraphLayout {
@@ -40,6 +55,7 @@ impl ParagraphLayout {
         Self {
             skia_font_collection: skia_layout::FontCollection::new(),
             font_families: HashSet::new(),
+            small_caps_fonts: HashSet::new(),
         }
     }
 
@@ -53,6 +69,11 @@ impl ParagraphLayout {
             for (attributes, data) in font_family.as_ref().iter() {
                 self.font_families.insert(family_name.clone());
 
+                if data.small_caps().is_some() {
+                    self.small_caps_fonts
+                        .insert((family_name.clone(), *attributes));
+                }
+
                 let data = Data::new_copy(data.as_bytes());
                 let typeface = Typeface::from_data(data, None).ok_or_else(|| {
                     InternalServerError::SkiaTypefaceFailure {
@@ -73,15 +94,220 @@ impl ParagraphLayout {
         Ok(self)
     }
 
+    /// Whether the font has small capitals that `smcp` turns on
+    pub fn has_small_caps(&self, family_name: &str, attributes: &FontAttributes) -> bool {
+        self.small_caps_fonts
+            .contains(&(family_name.to_owned(), *attributes))
+    }
+
     pub fn calculate_layout(
         &self,
         layout_style: ParagraphStyle,
         rich_text: &RichText,
         width: Pt,
     ) -> Result<RenderedTextBlock, DocumentGenerationError> {
+        let paragraph = self.build_paragraph(layout_style, rich_text, width)?;
+
+        // Skia stretches the whitespace of justified lines but the PDF writer
+        // places the text itself, so we need to know how much space skia
+        // added. A left aligned paragraph breaks the same way and has the
+        // natural width of every line.
+        let natural_style = ParagraphStyle {
+            align: TextAlign::Left,
+            ..layout_style
+        };
+        let natural_line_widths: Option<Vec<_>> = match layout_style.align {
+            TextAlign::Justify => Some(
+                self.build_paragraph(natural_style, rich_text, width)?
+                    .get_line_metrics()
+                    .iter()
+                    .map(|line_metrics| Pt(line_metrics.width))
+                    .collect(),
+            ),
+            _ => None,
+        };
+
+        let line_metrics = paragraph.get_line_metrics();
+
+        // Skia draws the ellipsis itself, so the text it replaces on the last
+        // line has to be worked out again
+        let ellipsized_line = match layout_style.text_overflow {
+            TextOverflow::Ellipsis if paragraph.did_exceed_max_lines() => {
+                line_metrics.len().checked_sub(1)
+            }
+            _ => None,
+        };
+
+        let mut rendered_text_block = RenderedTextBlock { lines: vec![] };
+
+        for (line_index, line_metrics) in line_metrics.iter().enumerate() {
+            let is_ellipsized = ellipsized_line == Some(line_index);
+
+            let mut line_text = if is_ellipsized {
+                let rest = rich_text.substr(line_metrics.start_index, rich_text.char_count())?;
+
+                self.ellipsize(&rest, width)?
+            } else {
+                rich_text.substr(line_metrics.start_index, line_metrics.end_index)?
+            };
+            let runs = RunMetrics::for_line(&paragraph, line_metrics, &line_text);
+
+            // Skia breaks lines at soft hyphens without drawing a hyphen, so
+            // one is added here and hangs past the width skia gave the line
+            let hyphen_width = if is_ellipsized {
+                line_text = with_ellipsis(line_text);
+                Pt(0.)
+            } else if resolve_soft_hyphens(&mut line_text) {
+                self.measure_hyphen(&line_text)?
+            } else {
+                Pt(0.)
+            };
+
+            let word_spacing = natural_line_widths
+                .as_ref()
+                .and_then(|widths| widths.get(line_index))
+                .map(|&natural_width| {
+                    let patches = whitespace_patches(&line_text.to_string());
+                    let extra_width = Pt(line_metrics.width) - natural_width - hyphen_width;
+
+                    if patches == 0 {
+                        Pt(0.)
+                    } else {
+                        Pt(extra_width.0 / patches as f64)
+                    }
+                })
+                .unwrap_or(Pt(0.));
+
+            let mut rendered_line_metrics = LineMetrics::from(line_metrics);
+
+            match layout_style.align {
+                // The hyphen takes up part of the space justification adds
+                TextAlign::Justify => {}
+                TextAlign::Right | TextAlign::End => {
+                    rendered_line_metrics.left -= hyphen_width;
+                    rendered_line_metrics.width += hyphen_width;
+                }
+                TextAlign::Center => {
+                    rendered_line_metrics.left -= Pt(hyphen_width.0 / 2.);
+                    rendered_line_metrics.width += hyphen_width;
+                }
+                TextAlign::Start | TextAlign::Left => {
+                    rendered_line_metrics.width += hyphen_width;
+                }
+            }
+
+            rendered_text_block.lines.push(RenderedTextLine {
+                rich_text: line_text,
+                line_metrics: rendered_line_metrics,
+                word_spacing,
+                runs,
+            });
+        }
+
+        Ok(rendered_text_block)
+    }
+
+    /// Lays out `rich_text` within `size`, reducing every font size by the
+    /// same factor until it fits or the largest is `min_font_size`.
+    pub fn shrink_to_fit(
+        &self,
+        layout_style: ParagraphStyle,
+        rich_text: &RichText,
+        size: Size<Pt>,
+        min_font_size: Pt,
+    ) -> Result<RenderedTextBlock, DocumentGenerationError> {
+        let fits = |text_block: &RenderedTextBlock| {
+            text_block.height() <= size.height && text_block.width() <= size.width
+        };
+
+        let text_block = self.calculate_layout(layout_style, rich_text, size.width)?;
+        if fits(&text_block) {
+            return Ok(text_block);
+        }
+
+        let largest_font_size = rich_text
+            .0
+            .iter()
+            .map(|span| span.size.0)
+            .fold(0., f64::max);
+
+        let min_scale = (min_font_size.0 / largest_font_size).min(1.);
+        let mut best =
+            self.calculate_layout(layout_style, &rich_text.scaled(min_scale), size.width)?;
+
+        if !fits(&best) {
+            return Ok(best);
+        }
+
+        let (mut fitting_scale, mut overflowing_scale) = (min_scale, 1.);
+
+        for _ in 0..SHRINK_STEPS {
+            let scale = (fitting_scale + overflowing_scale) / 2.;
+            let text_block =
+                self.calculate_layout(layout_style, &rich_text.scaled(scale), size.width)?;
+
+            if fits(&text_block) {
+                fitting_scale = scale;
+                best = text_block;
+            } else {
+                overflowing_scale = scale;
+            }
+        }
+
+        Ok(best)
+    }
+
+    /// The longest start of `text` that still fits within `width` once it ends
+    /// with an ellipsis
+    fn ellipsize(&self, text: &RichText, width: Pt) -> Result<RichText, DocumentGenerationError> {
+        let (mut fitting_count, mut overflowing_count) = (0, text.char_count() + 1);
+
+        while overflowing_count - fitting_count > 1 {
+            let count = (fitting_count + overflowing_count) / 2;
+
+            if self.measure(&with_ellipsis(text.prefix(count)?))? <= width {
+                fitting_count = count;
+            } else {
+                overflowing_count = count;
+            }
+        }
+
+        text.prefix(fitting_count)
+    }
+
+    /// The width of the hyphen drawn at the end of `line_text`
+    fn measure_hyphen(&self, line_text: &RichText) -> Result<Pt, DocumentGenerationError> {
+        match line_text.0.last() {
+            Some(span) => self.measure(&RichText(vec![RichTextSpan {
+                text: String::from("-"),
+                ..span.clone()
+            }])),
+            None => Ok(Pt(0.)),
+        }
+    }
+
+    /// The width of `rich_text` on a single line
+    fn measure(&self, rich_text: &RichText) -> Result<Pt, DocumentGenerationError> {
+        let paragraph =
+            self.build_paragraph(ParagraphStyle::left(), rich_text, Pt(f64::INFINITY))?;
+
+        Ok(Pt(paragraph.max_intrinsic_width().into()))
+    }
+
+    fn build_paragraph(
+        &self,
+        layout_style: ParagraphStyle,
+        rich_text: &RichText,
+        width: Pt,
+    ) -> Result<skia_layout::Paragraph, DocumentGenerationError> {
         let mut paragraph_style = skia_layout::ParagraphStyle::new();
 
         paragraph_style.set_text_align(layout_style.align.into());
+        paragraph_style.set_max_lines(layout_style.max_lines);
+
+        if layout_style.text_overflow == TextOverflow::Ellipsis {
+            paragraph_style.set_ellipsis(ELLIPSIS);
+        }
         // paragraph_style.set_text_height_behavior(skia_layout::TextHeightBehavior::DisableAll);
 
         let mut paragraph_builder =
@@ -97,17 +323,22 @@ impl ParagraphLayout {
         let mut paragraph = paragraph_builder.build();
         paragraph.layout(width.0 as f32);
 
-        let mut rendered_text_block = RenderedTextBlock { lines: vec![] };
+        Ok(paragraph)
+    }
+}
 
-        let mut height = Pt(0.);
-        for line_metrics in paragraph.get_line_metrics().iter() {
-            height += Pt(line_metrics.height as f64);
-            rendered_text_block.lines.push(RenderedTextLine {
-                rich_text: rich_text.substr(line_metrics.start_index, line_metrics.end_index)?,
-                line_metrics: line_metrics.into(),
-            });
-        }
+/// `text` with its soft hyphens, spacers and trailing whitespace removed and
+/// an ellipsis added
+fn with_ellipsis(mut text: RichText) -> RichText {
+    for span in text.0.iter_mut() {
+        span.text
+            .retain(|char| char != SOFT_HYPHEN && char != SPACER);
+    }
 
-        Ok(rendered_text_block)
+    if let Some(span) = text.0.last_mut() {
+        span.text.truncate(span.text.trim_end().len());
+        span.text.push_str(ELLIPSIS);
     }
+
+    text
 }
@@ -28,6 +28,8 @@ impl ParagraphLayout {
         span_style.set_height(span.line_height as f32);
         span_style.set_font_size(span.size.0 as f32);
         span_style.set_letter_spacing(span.letter_spacing.0 as f32);
+        // Skia shifts down, the way y grows
+        span_style.set_baseline_shift(-span.baseline_shift.0 as f32);
 
         if !self.font_families.contains(&span.font_family) {
             return Err(
@@ -41,6 +43,10 @@ impl ParagraphLayout {
         span_style.set_font_families(&[&span.font_family]);
         span_style.set_font_style(skia_font_style);
 
+        if span.small_caps {
+            span_style.add_font_feature("smcp", 1);
+        }
+
         Ok(span_style)
     }
 }
@@ -1,11 +1,16 @@
 use crate::{rich_text::RichText, values::Pt};
 
-use super::LineMetrics;
+use super::{LineMetrics, RunMetrics};
 
 #[derive(Debug, Clone)]
 pub struct RenderedTextLine {
     pub rich_text: RichText,
     pub line_metrics: LineMetrics,
+    /// Extra space added to the start of every run of whitespace (other than
+    /// trailing whitespace) when the line is justified
+    pub word_spacing: Pt,
+    /// Where each span of `rich_text` was laid out
+    pub runs: Vec<RunMetrics>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -35,3 +40,31 @@ impl RenderedTextBlock {
             .unwrap_or(Pt(0.))
     }
 }
+
+/// The number of runs of whitespace that skia spreads the extra space of a
+/// justified line across. Trailing whitespace isn't drawn so it isn't counted.
+pub(super) fn whitespace_patches(line: &str) -> usize {
+    line.trim_end()
+        .chars()
+        .fold((0, false), |(patches, in_whitespace), char| {
+            match (char.is_whitespace(), in_whitespace) {
+                (true, false) => (patches + 1, true),
+                (is_whitespace, _) => (patches, is_whitespace),
+            }
+        })
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_whitespace_patches() {
+        assert_eq!(whitespace_patches("justified"), 0);
+        assert_eq!(whitespace_patches("a justified line"), 2);
+        assert_eq!(whitespace_patches("a   justified\tline"), 2);
+        assert_eq!(whitespace_patches("trailing whitespace   "), 1);
+        assert_eq!(whitespace_patches(" leading"), 1);
+    }
+}
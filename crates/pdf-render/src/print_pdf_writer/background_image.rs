@@ -0,0 +1,240 @@
+//! Background images, which are decoded once per path and tiled across the
+//!  box with `Do`.
+//!
+//! Like gradients, the images are referred to by name and added to the
+//!  written PDF as XObjects afterwards, each with a soft mask if it has an
+//!  alpha channel.
+
+use printpdf::lopdf::{content::Operation, Dictionary, Document, Object, Stream};
+
+use crate::{
+    error::{DocumentGenerationError, UserInputError},
+    stylesheet::Style,
+    values::{Pt, Rect, Size},
+};
+
+use super::{
+    corners::{clip, RoundedRect},
+    resources::add_page_resources,
+    PrintPdfWriter,
+};
+
+/// The most tiles drawn along each side of a box, so that a tiny tile can't
+/// flood the page's content
+const MAX_TILES_PER_SIDE: usize = 500;
+
+/// A decoded image, in 8 bit RGB
+pub(super) struct LoadedImage {
+    width: u32,
+    height: u32,
+    rgb: Vec<u8>,
+    alpha: Option<Vec<u8>>,
+}
+
+fn image_name(index: usize) -> String {
+    format!("Image{}", index)
+}
+
+impl<'a> PrintPdfWriter<'a> {
+    /// The index of the image at `path`, which is read and decoded the first
+    /// time it's used
+    pub(super) fn load_image(&mut self, path: &str) -> Result<usize, DocumentGenerationError> {
+        if let Some(&index) = self.image_indices.get(path) {
+            return Ok(index);
+        }
+
+        let bytes = std::fs::read(path).map_err(|source| UserInputError::ImageReadError {
+            source,
+            path: path.to_owned(),
+        })?;
+
+        let image =
+            image::load_from_memory(&bytes).map_err(|source| UserInputError::ImageDecodeError {
+                source,
+                path: path.to_owned(),
+            })?;
+
+        let alpha = image.color().has_alpha().then(|| {
+            image
+                .to_rgba8()
+                .pixels()
+                .map(|pixel| pixel[3])
+                .collect::<Vec<u8>>()
+        });
+
+        self.images.push(LoadedImage {
+            width: image.width(),
+            height: image.height(),
+            rgb: image.to_rgb8().into_raw(),
+            alpha,
+        });
+
+        let index = self.images.len() - 1;
+        self.image_indices.insert(path.to_owned(), index);
+
+        Ok(index)
+    }
+
+    /// Tiles the style's background image across the padding box of `rect`,
+    /// and out into the border, clipped to the border's corners
+    pub(super) fn draw_background_image(
+        &mut self,
+        page_index: usize,
+        rect: &Rect<Pt>,
+        style: &Style::Unmergeable,
+    ) -> Result<(), DocumentGenerationError> {
+        let path = match &style.background_image {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let index = self.load_image(path)?;
+        let image = &self.images[index];
+
+        let natural = Size {
+            width: Pt::from_px(f64::from(image.width), self.dpi),
+            height: Pt::from_px(f64::from(image.height), self.dpi),
+        };
+
+        let (width, radius) = (&style.border.width, &style.border.radius);
+        let area = Size {
+            width: rect.width - width.horizontal(),
+            height: rect.height - width.vertical(),
+        };
+
+        let tile = style.background_size.tile_size(natural, &area);
+
+        if tile.width <= Pt(0.) || tile.height <= Pt(0.) {
+            return Ok(());
+        }
+
+        // Measured from the padding box's top left corner, going down
+        let first_x = style.background_position.x.resolve(tile.width, area.width);
+        let first_y = style
+            .background_position
+            .y
+            .resolve(tile.height, area.height);
+
+        let xs = tile_offsets(
+            first_x.0,
+            tile.width.0,
+            -width.left.0,
+            area.width.0 + width.right.0,
+            style.background_repeat.repeats_x(),
+        );
+        let ys = tile_offsets(
+            first_y.0,
+            tile.height.0,
+            -width.top.0,
+            area.height.0 + width.bottom.0,
+            style.background_repeat.repeats_y(),
+        );
+
+        let (left, top) = (rect.left.0 + width.left.0, rect.top.0 - width.top.0);
+        let name = image_name(index);
+
+        let layer = self.get_base_layer(page_index);
+
+        layer.save_graphics_state();
+
+        RoundedRect::new(rect, radius).add_subpath(&layer);
+        clip(&layer, "W");
+
+        for &y in &ys {
+            for &x in &xs {
+                layer.save_graphics_state();
+
+                // Images fill the unit square, which is scaled to the tile
+                let transform = [
+                    tile.width.0,
+                    0.,
+                    0.,
+                    tile.height.0,
+                    left + x,
+                    top - y - tile.height.0,
+                ];
+
+                layer.add_operation(Operation::new(
+                    "cm",
+                    transform.into_iter().map(Object::Real).collect(),
+                ));
+                layer.add_operation(Operation::new(
+                    "Do",
+                    vec![Object::Name(name.clone().into_bytes())],
+                ));
+                layer.restore_graphics_state();
+            }
+        }
+
+        layer.restore_graphics_state();
+
+        Ok(())
+    }
+}
+
+/// Where each tile along one side starts, from `first`, for tiles of `length`
+/// that reach between `start` and `end`
+fn tile_offsets(first: f64, length: f64, start: f64, end: f64, repeats: bool) -> Vec<f64> {
+    if !repeats {
+        return vec![first];
+    }
+
+    let skipped = ((first - start) / length).ceil();
+    let first = first - skipped * length;
+
+    (0..MAX_TILES_PER_SIDE)
+        .map(|index| first + index as f64 * length)
+        .take_while(|&offset| offset < end)
+        .collect()
+}
+
+/// Adds `images` to every page of `document`
+pub(super) fn add_images(
+    document: &mut Document,
+    images: &[LoadedImage],
+) -> Result<(), printpdf::lopdf::Error> {
+    let images: Vec<(String, Object)> = images
+        .iter()
+        .enumerate()
+        .map(|(index, image)| {
+            let mut xobject = image_dictionary(image, b"DeviceRGB");
+
+            if let Some(alpha) = &image.alpha {
+                let mask = Stream::new(image_dictionary(image, b"DeviceGray"), alpha.clone());
+                xobject.set("SMask", Object::Reference(document.add_object(mask)));
+            }
+
+            let image_id = document.add_object(Stream::new(xobject, image.rgb.clone()));
+
+            (image_name(index), Object::Reference(image_id))
+        })
+        .collect();
+
+    add_page_resources(document, "XObject", &images)
+}
+
+fn image_dictionary(image: &LoadedImage, color_space: &[u8]) -> Dictionary {
+    let mut dictionary = Dictionary::new();
+    dictionary.set("Type", Object::Name(b"XObject".to_vec()));
+    dictionary.set("Subtype", Object::Name(b"Image".to_vec()));
+    dictionary.set("Width", Object::Integer(i64::from(image.width)));
+    dictionary.set("Height", Object::Integer(i64::from(image.height)));
+    dictionary.set("ColorSpace", Object::Name(color_space.to_vec()));
+    dictionary.set("BitsPerComponent", Object::Integer(8));
+
+    dictionary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_cover_the_border_box() {
+        assert_eq!(
+            tile_offsets(15., 10., -5., 30., true),
+            vec![-5., 5., 15., 25.]
+        );
+        assert_eq!(tile_offsets(15., 10., -5., 30., false), vec![15.]);
+    }
+}
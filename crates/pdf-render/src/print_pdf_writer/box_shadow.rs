@@ -0,0 +1,117 @@
+//! Box shadows, which PDFs can't blur, so a blurred shadow is built from
+//!  layers of translucent fills that each reach a little further.
+
+use printpdf::{lopdf::content::Operation, PdfLayerReference};
+
+use crate::{
+    stylesheet::Style,
+    values::{Color, Pt, Rect},
+};
+
+use super::{
+    corners::{add_polygon, clip, RoundedRect},
+    PrintPdfWriter,
+};
+
+/// The most layers a blurred edge is faded with
+const MAX_BLUR_LAYERS: usize = 16;
+
+impl<'a> PrintPdfWriter<'a> {
+    /// Draws the shadow of the box `rect`, which goes beneath its background,
+    /// or within its border if the shadow is inset
+    pub(super) fn draw_box_shadow(
+        &mut self,
+        page_index: usize,
+        rect: &Rect<Pt>,
+        style: &Style::Unmergeable,
+    ) {
+        let shadow = &style.box_shadow;
+
+        if !shadow.is_visible() {
+            return;
+        }
+
+        let color = shadow.color.as_ref().unwrap_or(&style.color);
+        let (blur, spread) = (shadow.blur.0.max(0.), shadow.spread.0);
+
+        let layer = self.get_base_layer(page_index);
+        let border_box = RoundedRect::new(rect, &style.border.radius);
+
+        // Page coordinates go up, while the offset goes down
+        let moved =
+            |rounded: &RoundedRect| rounded.translate(shadow.offset_x.0, -shadow.offset_y.0);
+
+        // Anything the shadow could reach, for clipping and for the area
+        // around an inset shadow's hole
+        let extent = border_box
+            .spread(shadow.offset_x.0.abs() + shadow.offset_y.0.abs() + spread.abs() + blur + 1.);
+
+        layer.save_graphics_state();
+
+        if shadow.inset {
+            let padding_box = border_box.inset(
+                style.border.width.top.0,
+                style.border.width.right.0,
+                style.border.width.bottom.0,
+                style.border.width.left.0,
+            );
+
+            padding_box.add_subpath(&layer);
+            clip(&layer, "W");
+
+            // The shadow is everything around a hole, whose edge is blurred
+            let hole = moved(&padding_box).spread(-spread);
+
+            self.fill_blurred(&layer, color, blur, |layer, offset| {
+                add_polygon(layer, &extent.bounds());
+                hole.spread(-offset).add_subpath(layer);
+                layer.add_operation(Operation::new("f*", vec![]));
+            });
+        } else {
+            // Like in CSS, the shadow isn't seen through the box
+            add_polygon(&layer, &extent.bounds());
+            border_box.add_subpath(&layer);
+            clip(&layer, "W*");
+
+            let shadow_box = moved(&border_box).spread(spread);
+
+            self.fill_blurred(&layer, color, blur, |layer, offset| {
+                shadow_box.spread(offset).add_subpath(layer);
+                layer.add_operation(Operation::new("f", vec![]));
+            });
+        }
+
+        layer.restore_graphics_state();
+    }
+
+    /// Calls `fill` with how far each layer reaches past the shadow's edge,
+    /// from `blur / 2` to `-blur / 2`. Each layer is more opaque than the last,
+    /// so that wherever the first `n` of them overlap, `n / layers` of the
+    /// color's alpha shows.
+    fn fill_blurred(
+        &mut self,
+        layer: &PdfLayerReference,
+        color: &Color,
+        blur: f64,
+        fill: impl Fn(&PdfLayerReference, f64),
+    ) {
+        let layers = if blur > 0. {
+            (blur.ceil() as usize).clamp(2, MAX_BLUR_LAYERS)
+        } else {
+            1
+        };
+
+        self.set_fill_color(layer, color);
+
+        let mut shown = 0.;
+        for index in 0..layers {
+            let target = color.a * (index + 1) as f64 / layers as f64;
+            let alpha = 1. - (1. - target) / (1. - shown);
+
+            self.set_fill_alpha(layer, alpha);
+            fill(layer, blur * (0.5 - (index as f64 + 0.5) / layers as f64));
+
+            shown = target;
+        }
+    }
+}
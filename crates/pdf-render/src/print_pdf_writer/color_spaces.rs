@@ -0,0 +1,113 @@
+//! Spot colors, which printpdf can only write as their process alternate.
+//!
+//! Each ink gets a separation color space, which the content refers to by
+//!  name and which is added to the written PDF afterwards.
+
+use printpdf::{
+    lopdf::{content::Operation, Dictionary, Document, Object},
+    PdfLayerReference,
+};
+
+use crate::values::{Cmyk, Color, ColorComponents, SpotColor};
+
+use super::{resources::add_page_resources, PrintPdfWriter};
+
+/// An ink, as it's used on any page
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Separation {
+    name: String,
+    alternate: Cmyk,
+}
+
+fn color_space_name(index: usize) -> String {
+    format!("Separation{}", index)
+}
+
+impl<'a> PrintPdfWriter<'a> {
+    pub(super) fn set_fill_color(&mut self, layer: &PdfLayerReference, color: &Color) {
+        match &color.components {
+            ColorComponents::Spot(spot) => self.set_spot_color(layer, spot, "cs", "scn"),
+            _ => layer.set_fill_color(color.clone().into()),
+        }
+    }
+
+    pub(super) fn set_outline_color(&mut self, layer: &PdfLayerReference, color: &Color) {
+        match &color.components {
+            ColorComponents::Spot(spot) => self.set_spot_color(layer, spot, "CS", "SCN"),
+            _ => layer.set_outline_color(color.clone().into()),
+        }
+    }
+
+    fn set_spot_color(
+        &mut self,
+        layer: &PdfLayerReference,
+        spot: &SpotColor,
+        color_space_operator: &str,
+        color_operator: &str,
+    ) {
+        let separation = Separation {
+            name: spot.name.clone(),
+            alternate: spot.alternate.clone(),
+        };
+
+        let index = match self.separations.iter().position(|used| *used == separation) {
+            Some(index) => index,
+            None => {
+                self.separations.push(separation);
+                self.separations.len() - 1
+            }
+        };
+
+        layer.add_operation(Operation::new(
+            color_space_operator,
+            vec![Object::Name(color_space_name(index).into_bytes())],
+        ));
+        layer.add_operation(Operation::new(
+            color_operator,
+            vec![Object::Real(spot.tint)],
+        ));
+    }
+}
+
+/// Adds the color spaces of `separations` to every page of `document`
+pub(super) fn add_separations(
+    document: &mut Document,
+    separations: &[Separation],
+) -> Result<(), printpdf::lopdf::Error> {
+    let color_spaces: Vec<(String, Object)> = separations
+        .iter()
+        .enumerate()
+        .map(|(index, separation)| {
+            let Cmyk { c, m, y, k } = separation.alternate;
+
+            // Blends from no ink to the alternate at full strength
+            let mut tint_transform = Dictionary::new();
+            tint_transform.set("FunctionType", Object::Integer(2));
+            tint_transform.set("Domain", vec![Object::Integer(0), Object::Integer(1)]);
+            tint_transform.set("C0", vec![Object::Real(0.); 4]);
+            tint_transform.set(
+                "C1",
+                vec![
+                    Object::Real(c),
+                    Object::Real(m),
+                    Object::Real(y),
+                    Object::Real(k),
+                ],
+            );
+            tint_transform.set("N", Object::Integer(1));
+
+            let tint_transform_id = document.add_object(tint_transform);
+
+            let color_space_id = document.add_object(vec![
+                Object::Name(b"Separation".to_vec()),
+                Object::Name(separation.name.clone().into_bytes()),
+                Object::Name(b"DeviceCMYK".to_vec()),
+                Object::Reference(tint_transform_id),
+            ]);
+
+            (color_space_name(index), Object::Reference(color_space_id))
+        })
+        .collect();
+
+    add_page_resources(document, "ColorSpace", &color_spaces)
+}
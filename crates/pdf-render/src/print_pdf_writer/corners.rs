@@ -1,79 +1,349 @@
-use std::{cell::RefCell, collections::HashMap, ops::Range, rc::Rc};
+//! Rectangles with rounded corners, which backgrounds fill and borders are
+//!  built from.
 
-use printpdf::calculate_points_for_circle;
+use printpdf::{
+    lopdf::{content::Operation, Object},
+    PdfLayerReference, Point,
+};
 
-use crate::values::Pt;
+use crate::{
+    stylesheet::{BorderRadiusStyle, CornerRadius},
+    values::{Pt, Rect},
+};
 
-const TOP_LEFT_CORNER: Range<usize> = 12..16;
-const TOP_RIGHT_CORNER: Range<usize> = 0..4;
-const BOTTOM_RIGHT_CORNER: Range<usize> = 4..8;
-const BOTTOM_LEFT_CORNER: Range<usize> = 8..12;
+/// How far along its tangents the control points of a quarter ellipse are, as
+/// a fraction of its radius
+const KAPPA: f64 = 0.551915024494;
 
-const ORIGIN: printpdf::Point = printpdf::Point {
-    x: printpdf::Pt(0.),
-    y: printpdf::Pt(0.),
-};
+/// A rectangle in page coordinates (where y goes up), with each corner rounded
+/// along a quarter ellipse
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct RoundedRect {
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+    /// The horizontal and vertical radius of the top left, top right, bottom
+    /// right and bottom left corners
+    radii: [(f64, f64); 4],
+}
+
+/// Where a corner's curve starts and ends going clockwise, and its control
+/// points if it's rounded
+struct Corner {
+    start: (f64, f64),
+    controls: Option<((f64, f64), (f64, f64))>,
+    end: (f64, f64),
+}
 
-type LinePoint = (printpdf::Point, bool);
+impl RoundedRect {
+    /// `rect` with the corners given by `radius`. Like in CSS, if the corners
+    /// along any side would overlap, every radius is scaled down until they
+    /// just touch.
+    pub fn new(rect: &Rect<Pt>, radius: &BorderRadiusStyle::Unmergeable) -> Self {
+        let corner = |radius: CornerRadius| {
+            if radius.is_square() {
+                (0., 0.)
+            } else {
+                (radius.horizontal.0, radius.vertical.0)
+            }
+        };
+
+        let radii = [
+            corner(radius.top_left),
+            corner(radius.top_right),
+            corner(radius.bottom_right),
+            corner(radius.bottom_left),
+        ];
+
+        let (width, height) = (rect.width.0.max(0.), rect.height.0.max(0.));
+
+        let scale = [
+            (width, radii[0].0 + radii[1].0),
+            (height, radii[1].1 + radii[2].1),
+            (width, radii[2].0 + radii[3].0),
+            (height, radii[3].1 + radii[0].1),
+        ]
+        .iter()
+        .filter(|(_, radii)| *radii > 0.)
+        .map(|(side, radii)| side / radii)
+        .fold(1., f64::min);
+
+        Self {
+            left: rect.left.0,
+            top: rect.top.0,
+            right: rect.left.0 + width,
+            bottom: rect.top.0 - height,
+            radii: radii.map(|(horizontal, vertical)| (horizontal * scale, vertical * scale)),
+        }
+    }
 
-const ZERO_RADIUS_CORNER: &[LinePoint; 1] = &[(ORIGIN, false)];
+    /// The rectangle with each side moved inwards, whose corners stay the same
+    /// distance from the outer ones, as they do inside a border
+    pub fn inset(&self, top: f64, right: f64, bottom: f64, left: f64) -> Self {
+        let inner_left = (self.left + left).min(self.right);
+        let inner_top = (self.top - top).max(self.bottom);
 
-#[derive(Default)]
-pub(super) struct Circles(RefCell<HashMap<i32, Rc<Circle>>>);
+        let corner =
+            |(horizontal, vertical): (f64, f64), horizontal_inset: f64, vertical_inset: f64| {
+                let radius = (
+                    (horizontal - horizontal_inset).max(0.),
+                    (vertical - vertical_inset).max(0.),
+                );
 
-pub(super) struct Circle(Vec<LinePoint>, bool);
+                if radius.0 > 0. && radius.1 > 0. {
+                    radius
+                } else {
+                    (0., 0.)
+                }
+            };
 
-impl Circle {
-    pub fn top_left(&self) -> &[LinePoint] {
-        if self.1 {
-            &self.0[TOP_LEFT_CORNER]
-        } else {
-            ZERO_RADIUS_CORNER
+        Self {
+            left: inner_left,
+            top: inner_top,
+            right: (self.right - right).max(inner_left),
+            bottom: (self.bottom + bottom).min(inner_top),
+            radii: [
+                corner(self.radii[0], left, top),
+                corner(self.radii[1], right, top),
+                corner(self.radii[2], right, bottom),
+                corner(self.radii[3], left, bottom),
+            ],
         }
     }
 
-    pub fn top_right(&self) -> &[LinePoint] {
-        if self.1 {
-            &self.0[TOP_RIGHT_CORNER]
-        } else {
-            ZERO_RADIUS_CORNER
+    /// The rectangle moved outwards by `spread` on every side, or inwards if
+    /// it's negative, with its rounded corners growing or shrinking with it
+    pub fn spread(&self, spread: f64) -> Self {
+        if spread < 0. {
+            return self.inset(-spread, -spread, -spread, -spread);
+        }
+
+        let corner = |(horizontal, vertical): (f64, f64)| {
+            if horizontal > 0. && vertical > 0. {
+                (horizontal + spread, vertical + spread)
+            } else {
+                (0., 0.)
+            }
+        };
+
+        Self {
+            left: self.left - spread,
+            top: self.top + spread,
+            right: self.right + spread,
+            bottom: self.bottom - spread,
+            radii: self.radii.map(corner),
         }
     }
 
-    pub fn bottom_left(&self) -> &[LinePoint] {
-        if self.1 {
-            &self.0[BOTTOM_LEFT_CORNER]
-        } else {
-            ZERO_RADIUS_CORNER
+    pub fn translate(&self, x: f64, y: f64) -> Self {
+        Self {
+            left: self.left + x,
+            top: self.top + y,
+            right: self.right + x,
+            bottom: self.bottom + y,
+            radii: self.radii,
         }
     }
 
-    pub fn bottom_right(&self) -> &[LinePoint] {
-        if self.1 {
-            &self.0[BOTTOM_RIGHT_CORNER]
-        } else {
-            ZERO_RADIUS_CORNER
+    /// The corners of the rectangle, ignoring how they're rounded, clockwise
+    /// from the top left
+    pub fn bounds(&self) -> [(f64, f64); 4] {
+        [
+            (self.left, self.top),
+            (self.right, self.top),
+            (self.right, self.bottom),
+            (self.left, self.bottom),
+        ]
+    }
+
+    /// The corners clockwise from the top left, each going from the side
+    /// before it to the side after it
+    fn corners(&self) -> [Corner; 4] {
+        let [top_left, top_right, bottom_right, bottom_left] = self.radii;
+
+        // The corner of the rectangle, the direction the outline travels in
+        // before and after it, and its radius along those
+        let corners = [
+            (
+                (self.left, self.top),
+                (0., 1.),
+                (1., 0.),
+                top_left.1,
+                top_left.0,
+            ),
+            (
+                (self.right, self.top),
+                (1., 0.),
+                (0., -1.),
+                top_right.0,
+                top_right.1,
+            ),
+            (
+                (self.right, self.bottom),
+                (0., -1.),
+                (-1., 0.),
+                bottom_right.1,
+                bottom_right.0,
+            ),
+            (
+                (self.left, self.bottom),
+                (-1., 0.),
+                (0., 1.),
+                bottom_left.0,
+                bottom_left.1,
+            ),
+        ];
+
+        corners.map(|((x, y), before, after, radius_before, radius_after)| {
+            let start = (x - before.0 * radius_before, y - before.1 * radius_before);
+            let end = (x + after.0 * radius_after, y + after.1 * radius_after);
+
+            let controls = if radius_before > 0. && radius_after > 0. {
+                Some((
+                    (
+                        start.0 + before.0 * KAPPA * radius_before,
+                        start.1 + before.1 * KAPPA * radius_before,
+                    ),
+                    (
+                        end.0 - after.0 * KAPPA * radius_after,
+                        end.1 - after.1 * KAPPA * radius_after,
+                    ),
+                ))
+            } else {
+                None
+            };
+
+            Corner {
+                start,
+                controls,
+                end,
+            }
+        })
+    }
+
+    /// The points of the outline for a printpdf `Line`, which curves from any
+    /// point marked as true through the next two
+    pub fn outline(&self) -> Vec<(Point, bool)> {
+        let point = |(x, y): (f64, f64)| Point {
+            x: printpdf::Pt(x),
+            y: printpdf::Pt(y),
+        };
+
+        self.corners()
+            .iter()
+            .flat_map(|corner| match corner.controls {
+                Some((first, second)) => vec![
+                    (point(corner.start), true),
+                    (point(first), true),
+                    (point(second), false),
+                    (point(corner.end), false),
+                ],
+                None => vec![(point(corner.start), false)],
+            })
+            .collect()
+    }
+
+    /// Adds the outline to the layer's current path, so several outlines can
+    /// be painted together (i.e. as a ring with the even-odd rule)
+    pub fn add_subpath(&self, layer: &PdfLayerReference) {
+        for (index, corner) in self.corners().iter().enumerate() {
+            let operator = if index == 0 { "m" } else { "l" };
+            layer.add_operation(Operation::new(operator, reals(&[corner.start])));
+
+            if let Some((first, second)) = corner.controls {
+                layer.add_operation(Operation::new("c", reals(&[first, second, corner.end])));
+            }
         }
+
+        layer.add_operation(Operation::new("h", vec![]));
+    }
+
+    /// The part of the ring between this and `inner` that belongs to the top,
+    /// right, bottom or left side (by `index`), split from its neighbours by
+    /// the lines between their outer and inner corners
+    pub fn side_region(&self, inner: &Self, index: usize) -> [(f64, f64); 4] {
+        let (outer_corners, inner_corners) = (self.bounds(), inner.bounds());
+        let next = (index + 1) % 4;
+
+        [
+            outer_corners[index],
+            outer_corners[next],
+            inner_corners[next],
+            inner_corners[index],
+        ]
     }
 }
 
-impl Circles {
-    pub fn get(&self, radius: Pt) -> Rc<Circle> {
-        let approx_radius: i32 = (radius.0 * 100.) as i32;
+/// Adds the polygon through `points` to the layer's current path
+pub(super) fn add_polygon(layer: &PdfLayerReference, points: &[(f64, f64)]) {
+    for (index, &point) in points.iter().enumerate() {
+        let operator = if index == 0 { "m" } else { "l" };
+        layer.add_operation(Operation::new(operator, reals(&[point])));
+    }
+
+    layer.add_operation(Operation::new("h", vec![]));
+}
 
-        let mut cache = self.0.borrow_mut();
+/// Ends the current path as a clipping path, with the nonzero (`W`) or even-odd
+/// (`W*`) rule
+pub(super) fn clip(layer: &PdfLayerReference, operator: &str) {
+    layer.add_operation(Operation::new(operator, vec![]));
+    layer.add_operation(Operation::new("n", vec![]));
+}
 
-        if let Some(circle) = cache.get(&approx_radius) {
-            circle.clone()
-        } else {
-            let circle = Rc::new(Circle(
-                calculate_points_for_circle(radius, Pt(0.), Pt(0.)),
-                approx_radius != 0,
-            ));
+fn reals(points: &[(f64, f64)]) -> Vec<Object> {
+    points
+        .iter()
+        .flat_map(|&(x, y)| [Object::Real(x), Object::Real(y)])
+        .collect()
+}
 
-            cache.insert(approx_radius, circle.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            circle
+    fn rect(width: f64, height: f64) -> Rect<Pt> {
+        Rect {
+            left: Pt(0.),
+            top: Pt(height),
+            width: Pt(width),
+            height: Pt(height),
         }
     }
+
+    #[test]
+    fn scales_overlapping_radii() {
+        let radius = BorderRadiusStyle::Unmergeable {
+            top_left: CornerRadius {
+                horizontal: Pt(60.),
+                vertical: Pt(10.),
+            },
+            top_right: CornerRadius::new(Pt(40.)),
+            ..Default::default()
+        };
+
+        let rounded = RoundedRect::new(&rect(50., 100.), &radius);
+
+        // The top side needs 100pt, so everything is halved
+        assert_eq!(rounded.radii[0], (30., 5.));
+        assert_eq!(rounded.radii[1], (20., 20.));
+        assert_eq!(rounded.radii[2], (0., 0.));
+    }
+
+    #[test]
+    fn insets_corners() {
+        let rounded = RoundedRect::new(
+            &rect(100., 100.),
+            &BorderRadiusStyle::Unmergeable::new(Pt(10.)),
+        );
+
+        let inner = rounded.inset(4., 0., 20., 2.);
+
+        assert_eq!(
+            (inner.left, inner.top, inner.right, inner.bottom),
+            (2., 96., 100., 20.)
+        );
+        assert_eq!(inner.radii, [(8., 6.), (10., 6.), (0., 0.), (0., 0.)]);
+    }
 }
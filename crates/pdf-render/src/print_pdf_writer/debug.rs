@@ -3,7 +3,7 @@ use printpdf::{Line, Point, Rgb};
 use crate::{
     block_layout::paginated_layout::PaginatedNode,
     fonts::FontAttributes,
-    stylesheet::{BorderRadiusStyle, EdgeStyle, Style},
+    stylesheet::{BorderRadiusStyle, BorderStyle, EdgeColorStyle, EdgeStyle, Style},
     utils::debug_cursor::DebugCursor,
     values::{Color, Mm, Pt, Rect},
 };
@@ -48,28 +48,25 @@ impl<'a> PrintPdfWriter<'a> {
         self.draw_rect(
             page_index,
             margin_rect,
-            EdgeStyle::Unmergeable::new(Pt(1.)),
-            Some(Color::try_from("green").unwrap()),
+            &debug_border("green", Pt(10.)),
+            None,
             None,
-            Some(BorderRadiusStyle::Unmergeable::new(Pt(10.))),
         );
 
         self.draw_rect(
             page_index,
             border_rect,
-            EdgeStyle::Unmergeable::new(Pt(1.)),
-            Some(Color::try_from("red").unwrap()),
+            &debug_border("red", Pt(7.5)),
+            None,
             None,
-            Some(BorderRadiusStyle::Unmergeable::new(Pt(7.5))),
         );
 
         self.draw_rect(
             page_index,
             content_rect,
-            EdgeStyle::Unmergeable::new(Pt(1.)),
-            Some(Color::try_from("blue").unwrap()),
+            &debug_border("blue", Pt(5.)),
+            None,
             None,
-            Some(BorderRadiusStyle::Unmergeable::new(Pt(5.))),
         );
     }
 
@@ -137,3 +134,13 @@ impl<'a> PrintPdfWriter<'a> {
         }
     }
 }
+
+/// A thin outline, with its corners rounded by `radius`
+fn debug_border(color: &str, radius: Pt) -> BorderStyle::Unmergeable {
+    BorderStyle::Unmergeable {
+        width: EdgeStyle::Unmergeable::new(Pt(1.)),
+        color: EdgeColorStyle::Unmergeable::new(Color::try_from(color).unwrap()),
+        radius: BorderRadiusStyle::Unmergeable::new(radius),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,128 @@
+use printpdf::{Line, LineDashPattern, PdfLayerReference, Point};
+
+use crate::{
+    paragraph_layout::{RenderedTextBlock, RunMetrics},
+    stylesheet::{TextDecoration, TextDecorationLine, TextDecorationStyle},
+    values::Pt,
+};
+
+use super::PrintPdfWriter;
+
+impl<'a> PrintPdfWriter<'a> {
+    /// Strokes the decorations of every span in `text_block`, whose first line
+    /// starts at (`left`, `top`).
+    pub(super) fn draw_text_decorations(
+        &mut self,
+        page_index: usize,
+        left: Pt,
+        top: Pt,
+        text_block: &RenderedTextBlock,
+    ) {
+        let layer = self.get_base_layer(page_index);
+
+        layer.save_graphics_state();
+
+        let mut stroke_alpha = 1.;
+        let mut current_y = top;
+        for line in text_block.lines.iter() {
+            let baseline = current_y - line.line_metrics.ascent;
+
+            for (span, run) in line.rich_text.0.iter().zip(line.runs.iter()) {
+                if span.text_decoration.line != TextDecorationLine::None && run.width > Pt(0.) {
+                    let color = span.text_decoration.color.as_ref().unwrap_or(&span.color);
+
+                    if color.a != stroke_alpha {
+                        self.set_stroke_alpha(&layer, color.a);
+                        stroke_alpha = color.a;
+                    }
+
+                    self.set_outline_color(&layer, color);
+
+                    draw_decoration(
+                        &layer,
+                        &span.text_decoration,
+                        run,
+                        left + run.left,
+                        baseline + span.baseline_shift,
+                    );
+                }
+            }
+
+            current_y -= line.line_metrics.height;
+        }
+
+        layer.restore_graphics_state();
+    }
+}
+
+fn draw_decoration(
+    layer: &PdfLayerReference,
+    decoration: &TextDecoration::Unmergeable,
+    run: &RunMetrics,
+    left: Pt,
+    baseline: Pt,
+) {
+    // The font metrics give the top of the stroke, measured down from the
+    // baseline
+    let (position, font_thickness) = match decoration.line {
+        TextDecorationLine::Underline => (run.underline_position, run.underline_thickness),
+        TextDecorationLine::LineThrough => (run.strikeout_position, run.strikeout_thickness),
+        TextDecorationLine::Overline => (Pt(-run.ascent.0), run.underline_thickness),
+        TextDecorationLine::None => return,
+    };
+
+    let thickness = decoration.thickness.unwrap_or(font_thickness);
+    let center = position + Pt(thickness.0 / 2.);
+
+    // The second line of a double decoration goes away from the text
+    let offsets = match (decoration.style, decoration.line) {
+        (TextDecorationStyle::Double, TextDecorationLine::Overline) => {
+            vec![center, center - Pt(thickness.0 * 2.)]
+        }
+        (TextDecorationStyle::Double, _) => vec![center, center + Pt(thickness.0 * 2.)],
+        _ => vec![center],
+    };
+
+    let dash_pattern = match decoration.style {
+        TextDecorationStyle::Dashed => {
+            let dash = (thickness.0 * 3.).round().max(1.) as i64;
+
+            LineDashPattern {
+                dash_1: Some(dash),
+                gap_1: Some(dash),
+                ..Default::default()
+            }
+        }
+        _ => LineDashPattern::default(),
+    };
+
+    layer.set_line_dash_pattern(dash_pattern);
+    layer.set_outline_thickness(thickness.0);
+
+    for offset in offsets {
+        let y = baseline - offset;
+
+        layer.add_shape(Line {
+            points: vec![
+                (
+                    Point {
+                        x: left.into(),
+                        y: y.into(),
+                    },
+                    false,
+                ),
+                (
+                    Point {
+                        x: (left + run.width).into(),
+                        y: y.into(),
+                    },
+                    false,
+                ),
+            ],
+            is_closed: false,
+            has_fill: false,
+            has_stroke: true,
+            is_clipping_path: false,
+        });
+    }
+}
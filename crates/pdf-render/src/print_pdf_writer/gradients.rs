@@ -0,0 +1,224 @@
+//! Gradient backgrounds, which are filled with shading patterns.
+//!
+//! Like spot colors, the patterns are referred to by name and added to the
+//!  written PDF afterwards.
+
+use std::f64::consts::SQRT_2;
+
+use printpdf::{
+    lopdf::{content::Operation, Dictionary, Document, Object},
+    Line, PdfLayerReference, Point,
+};
+
+use crate::values::{
+    Color, ColorComponents, Gradient, GradientDirection, Pt, RadialShape, Rect, SpotColor,
+};
+
+use super::{resources::add_page_resources, PrintPdfWriter};
+
+fn pattern_name(index: usize) -> String {
+    format!("Gradient{}", index)
+}
+
+impl<'a> PrintPdfWriter<'a> {
+    /// Fills `outline` with `gradient`, sized to `rect`
+    pub(super) fn fill_gradient(
+        &mut self,
+        layer: &PdfLayerReference,
+        rect: &Rect<Pt>,
+        outline: Vec<(Point, bool)>,
+        gradient: &Gradient,
+    ) {
+        let pattern = match shading_pattern(rect, gradient) {
+            Some(pattern) => pattern,
+            None => return,
+        };
+
+        self.patterns.push(pattern);
+        let name = pattern_name(self.patterns.len() - 1);
+
+        layer.add_operation(Operation::new(
+            "cs",
+            vec![Object::Name(b"Pattern".to_vec())],
+        ));
+        layer.add_operation(Operation::new("scn", vec![Object::Name(name.into_bytes())]));
+
+        layer.add_shape(Line {
+            points: outline,
+            is_closed: true,
+            has_fill: true,
+            has_stroke: false,
+            is_clipping_path: false,
+        });
+    }
+}
+
+/// A pattern that paints `gradient` across `rect`, or None if the gradient has
+/// no size
+fn shading_pattern(rect: &Rect<Pt>, gradient: &Gradient) -> Option<Dictionary> {
+    let stops = gradient.resolved_stops();
+
+    let color_space: &[u8] = match stops.first()?.1.components {
+        ColorComponents::Rgb { .. } => b"DeviceRGB",
+        _ => b"DeviceCMYK",
+    };
+
+    let (left, top) = (rect.left.0, rect.top.0);
+    let (width, height) = (rect.width.0, rect.height.0);
+
+    let mut pattern = Dictionary::new();
+    pattern.set("Type", Object::Name(b"Pattern".to_vec()));
+    pattern.set("PatternType", Object::Integer(2));
+
+    let (shading_type, coords) = match gradient {
+        Gradient::Linear { direction, .. } => {
+            // CSS angles point up at 0 and turn clockwise, so they're measured
+            // from the y axis
+            let (x, y) = match *direction {
+                GradientDirection::Angle(degrees) => {
+                    let radians = degrees.to_radians();
+                    (radians.sin(), radians.cos())
+                }
+                // Perpendicular to the diagonal through the other corners
+                GradientDirection::Corner { right, top } => {
+                    let diagonal = width.hypot(height);
+                    let x = if right { height } else { -height };
+                    let y = if top { width } else { -width };
+
+                    (x / diagonal, y / diagonal)
+                }
+            };
+
+            // Long enough for the corners to get the end colors
+            let half_length = (width * x.abs() + height * y.abs()) / 2.;
+            let (center_x, center_y) = (left + width / 2., top - height / 2.);
+
+            if half_length <= 0. {
+                return None;
+            }
+
+            (
+                2,
+                vec![
+                    center_x - x * half_length,
+                    center_y - y * half_length,
+                    center_x + x * half_length,
+                    center_y + y * half_length,
+                ],
+            )
+        }
+        Gradient::Radial {
+            shape,
+            center: (x, y),
+            ..
+        } => {
+            let (center_x, center_y) = (left + x * width, top - y * height);
+            let farthest_x = x.max(1. - x) * width;
+            let farthest_y = y.max(1. - y) * height;
+
+            match shape {
+                RadialShape::Circle => {
+                    let radius = farthest_x.hypot(farthest_y);
+
+                    if radius <= 0. {
+                        return None;
+                    }
+
+                    (3, vec![center_x, center_y, 0., center_x, center_y, radius])
+                }
+                // PDF shadings are circles, so the pattern squashes one
+                RadialShape::Ellipse => {
+                    let (radius_x, radius_y) = (farthest_x * SQRT_2, farthest_y * SQRT_2);
+
+                    if radius_x <= 0. || radius_y <= 0. {
+                        return None;
+                    }
+
+                    pattern.set(
+                        "Matrix",
+                        reals(&[1., 0., 0., radius_y / radius_x, 0., center_y]),
+                    );
+
+                    (3, vec![center_x, 0., 0., center_x, 0., radius_x])
+                }
+            }
+        }
+    };
+
+    let mut shading = Dictionary::new();
+    shading.set("ShadingType", Object::Integer(shading_type));
+    shading.set("ColorSpace", Object::Name(color_space.to_vec()));
+    shading.set("Coords", reals(&coords));
+    shading.set("Function", stops_function(&stops));
+    shading.set("Extend", vec![Object::Boolean(true), Object::Boolean(true)]);
+
+    pattern.set("Shading", shading);
+
+    Some(pattern)
+}
+
+fn reals(values: &[f64]) -> Object {
+    Object::Array(values.iter().map(|&value| Object::Real(value)).collect())
+}
+
+fn components(color: &Color) -> Object {
+    match &color.components {
+        ColorComponents::Rgb { r, g, b } => reals(&[*r, *g, *b]),
+        ColorComponents::Cmyk(cmyk)
+        | ColorComponents::Spot(SpotColor {
+            alternate: cmyk, ..
+        }) => reals(&[cmyk.c, cmyk.m, cmyk.y, cmyk.k]),
+    }
+}
+
+/// Blends between each pair of `stops`, which start at 0 and end at 1
+fn stops_function(stops: &[(f64, &Color)]) -> Dictionary {
+    let blends: Vec<Object> = stops
+        .windows(2)
+        .map(|pair| {
+            let mut blend = Dictionary::new();
+            blend.set("FunctionType", Object::Integer(2));
+            blend.set("Domain", reals(&[0., 1.]));
+            blend.set("C0", components(pair[0].1));
+            blend.set("C1", components(pair[1].1));
+            blend.set("N", Object::Integer(1));
+
+            Object::Dictionary(blend)
+        })
+        .collect();
+
+    let mut function = Dictionary::new();
+    function.set("FunctionType", Object::Integer(3));
+    function.set("Domain", reals(&[0., 1.]));
+    function.set(
+        "Bounds",
+        reals(
+            &stops[1..stops.len() - 1]
+                .iter()
+                .map(|(position, _)| *position)
+                .collect::<Vec<_>>(),
+        ),
+    );
+    function.set("Encode", reals(&[0., 1.].repeat(blends.len())));
+    function.set("Functions", blends);
+
+    function
+}
+
+/// Adds `patterns` to every page of `document`
+pub(super) fn add_patterns(
+    document: &mut Document,
+    patterns: &[Dictionary],
+) -> Result<(), printpdf::lopdf::Error> {
+    let patterns: Vec<(String, Object)> = patterns
+        .iter()
+        .enumerate()
+        .map(|(index, pattern)| {
+            let pattern_id = document.add_object(pattern.clone());
+
+            (pattern_name(index), Object::Reference(pattern_id))
+        })
+        .collect();
+
+    add_page_resources(document, "Pattern", &patterns)
+}
@@ -0,0 +1,148 @@
+//! The groups nodes are drawn within: transparency groups for nodes with an
+//!  `opacity`, and clipping paths for nodes with `overflow: hidden`.
+//!
+//! Nodes are drawn in order, so a group is opened before the first node
+//!  within it and closed once a node outside of it is drawn. Groups can't
+//!  span pages, so a node's groups are opened again on every page it's on.
+
+use printpdf::{lopdf::content::Operation, PdfLayerReference};
+
+use crate::{
+    block_layout::paginated_layout::{ClipGroup, NodeGroup},
+    stylesheet::{BorderRadiusStyle, CornerRadius},
+    values::{Pt, Rect},
+};
+
+use super::{
+    corners::{clip, RoundedRect},
+    CurrentStyles, PrintPdfWriter,
+};
+
+/// A group that's been opened on a page but not closed yet
+pub(super) struct OpenGroup {
+    group: NodeGroup,
+    page_index: usize,
+    /// What the page's styles were before the group, which they are again
+    /// once it's closed
+    outer_styles: CurrentStyles,
+}
+
+impl<'a> PrintPdfWriter<'a> {
+    /// Closes the open groups a node on `page_index` isn't within, and opens
+    /// the ones it is
+    pub(super) fn enter_groups(&mut self, page_index: usize, groups: &[NodeGroup]) {
+        let shared_count = self
+            .open_groups
+            .iter()
+            .zip(groups)
+            .take_while(|(open, group)| open.page_index == page_index && open.group.is_same(group))
+            .count();
+
+        while self.open_groups.len() > shared_count {
+            self.close_group();
+        }
+
+        for group in &groups[shared_count..] {
+            let layer = self.get_base_layer(page_index);
+
+            let outer_styles = match group {
+                NodeGroup::Opacity(opacity_group) => {
+                    self.begin_opacity_group(&layer, opacity_group.opacity);
+
+                    // The group starts out opaque, and whatever it changes is
+                    // undone once it's painted
+                    std::mem::take(&mut self.current_style_by_page[page_index])
+                }
+                NodeGroup::Clip(clip_group) => {
+                    layer.save_graphics_state();
+                    self.clip_to_fragment(&layer, clip_group);
+
+                    self.current_style_by_page[page_index].clone()
+                }
+            };
+
+            self.open_groups.push(OpenGroup {
+                group: group.clone(),
+                page_index,
+                outer_styles,
+            });
+        }
+    }
+
+    pub(super) fn close_groups(&mut self) {
+        while !self.open_groups.is_empty() {
+            self.close_group();
+        }
+    }
+
+    fn close_group(&mut self) {
+        if let Some(open_group) = self.open_groups.pop() {
+            let layer = self.get_base_layer(open_group.page_index);
+
+            match open_group.group {
+                NodeGroup::Opacity(_) => layer.add_operation(Operation::new("EMC", vec![])),
+                NodeGroup::Clip(_) => layer.restore_graphics_state(),
+            }
+
+            self.current_style_by_page[open_group.page_index] = open_group.outer_styles;
+        }
+    }
+
+    /// Clips to the part of the node's padding box that's on the page.
+    /// Where the node carries on over a page break, the clip reaches the edge
+    /// of the page, and the corners on that side are square.
+    fn clip_to_fragment(&self, layer: &PdfLayerReference, clip_group: &ClipGroup) {
+        let content_height = self.page_size.height - self.page_margins.vertical();
+
+        let layout = &clip_group.fragment;
+        let mut top = layout.top;
+        let mut bottom = layout.bottom();
+
+        let starts_here = clip_group.starts_on_page;
+        let ends_here = clip_group.ends_on_page;
+
+        // Unless the node ended above the page, which leaves nothing to draw
+        // within it
+        if !starts_here && bottom > Pt(-self.page_margins.top.0) {
+            top = Pt(-self.page_margins.top.0);
+        }
+        if !ends_here {
+            bottom = content_height + self.page_margins.bottom;
+        }
+
+        let rect = Rect {
+            left: layout.left + self.page_margins.left,
+            top: self.page_size.height - (top + self.page_margins.top),
+            width: layout.width,
+            height: bottom - top,
+        };
+
+        // Corners that are on another page are square
+        let corner = |radius: CornerRadius, is_on_page: bool| {
+            if is_on_page {
+                radius
+            } else {
+                CornerRadius::default()
+            }
+        };
+
+        let radius = &clip_group.border_radius;
+        let radius = BorderRadiusStyle::Unmergeable {
+            top_left: corner(radius.top_left, starts_here),
+            top_right: corner(radius.top_right, starts_here),
+            bottom_right: corner(radius.bottom_right, ends_here),
+            bottom_left: corner(radius.bottom_left, ends_here),
+        };
+
+        let width = &clip_group.border_width;
+        let padding_box = RoundedRect::new(&rect, &radius).inset(
+            if starts_here { width.top.0 } else { 0. },
+            width.right.0,
+            if ends_here { width.bottom.0 } else { 0. },
+            width.left.0,
+        );
+
+        padding_box.add_subpath(layer);
+        clip(layer, "W");
+    }
+}
@@ -1,32 +1,48 @@
 //! This is ultimately what takes nodes that have been styled
 //!  and laid out and writes them to a PDF.
 use std::{
+    collections::{BTreeSet, HashMap},
     io::{BufWriter, Write},
     rc::Rc,
 };
 
 use printpdf::{
-    IndirectFontRef, PdfDocument, PdfDocumentReference, PdfLayerIndex, PdfLayerReference,
-    PdfPageIndex, TextMatrix,
+    lopdf::Dictionary, IndirectFontRef, PdfDocument, PdfDocumentReference, PdfLayerIndex,
+    PdfLayerReference, PdfPageIndex, TextMatrix,
 };
 
+mod background_image;
+mod box_shadow;
+mod color_spaces;
 mod corners;
 mod debug;
+mod decorations;
 mod font_lookup;
+mod gradients;
+mod groups;
+mod output_intent;
 mod rect;
+mod resources;
+mod span_backgrounds;
+mod transparency;
+
+pub use output_intent::OutputIntent;
 
 use crate::{
     block_layout::paginated_layout::{DrawableNode, PaginatedNode},
     document_builder::UnstructuredDocumentWriter,
     error::{DocumentGenerationError, InternalServerError},
-    fonts::{FontCollection, FontId},
+    fonts::{FontCollection, FontId, SmallCaps},
     paragraph_layout::RenderedTextBlock,
     rich_text::RichTextSpan,
     stylesheet::{EdgeStyle, Style},
-    values::{Color, Mm, Pt, Size},
+    values::{Color, Mm, Pt, Size, DEFAULT_DPI},
 };
 
-use self::{corners::Circles, font_lookup::FontLookup};
+use self::{
+    background_image::LoadedImage, color_spaces::Separation, font_lookup::FontLookup,
+    groups::OpenGroup, transparency::Alpha,
+};
 
 #[derive(Clone, Default)]
 struct CurrentStyles {
@@ -39,6 +55,10 @@ struct CurrentStyles {
 
 static BASE_LAYER_NAME: &str = "Layer 1";
 
+/// The width, in thousandths of an em, of glyphs missing from an embedded
+/// font's widths, which only cover the glyphs its characters map to
+const DEFAULT_GLYPH_WIDTH: f64 = 1000.;
+
 pub struct PrintPdfWriter<'a> {
     raw_pdf_doc: PdfDocumentReference,
     fonts: FontLookup,
@@ -47,7 +67,21 @@ pub struct PrintPdfWriter<'a> {
     page_size: Size<Pt>,
     page_margins: EdgeStyle::Unmergeable,
     current_style_by_page: Vec<CurrentStyles>,
-    circle_cache: Circles,
+    /// The graphics states the content refers to
+    alphas: BTreeSet<Alpha>,
+    open_groups: Vec<OpenGroup>,
+    /// The inks of the spot colors used, in the order their color spaces are
+    /// named
+    separations: Vec<Separation>,
+    /// The gradients drawn, which are each a pattern of their own
+    patterns: Vec<Dictionary>,
+    /// The background images drawn, which are each an XObject of their own
+    images: Vec<LoadedImage>,
+    /// The index of each image, by the path it was loaded from
+    image_indices: HashMap<String, usize>,
+    /// Pixels per inch, which an image's own size is measured in
+    dpi: f64,
+    output_intent: Option<OutputIntent>,
 }
 
 impl<'a> PrintPdfWriter<'a> {
@@ -74,10 +108,25 @@ impl<'a> PrintPdfWriter<'a> {
             page_margins: page_margins.into(),
             page_size: dimensions.into(),
             current_style_by_page: vec![CurrentStyles::default()],
-            circle_cache: Default::default(),
+            alphas: BTreeSet::new(),
+            open_groups: vec![],
+            separations: vec![],
+            patterns: vec![],
+            images: vec![],
+            image_indices: HashMap::new(),
+            dpi: DEFAULT_DPI,
+            output_intent: None,
         }
     }
 
+    pub fn set_output_intent(&mut self, output_intent: OutputIntent) {
+        self.output_intent = Some(output_intent);
+    }
+
+    pub fn set_dpi(&mut self, dpi: f64) {
+        self.dpi = dpi;
+    }
+
     pub fn get_font(
         &self,
         font_id: FontId,
@@ -104,12 +153,34 @@ impl<'a> PrintPdfWriter<'a> {
     }
 
     pub fn save<W: Write>(
-        self,
+        mut self,
         pdf_doc_writer: W,
     ) -> Result<W, crate::error::DocumentGenerationError> {
+        self.close_groups();
+
         let mut buf_writer = BufWriter::new(pdf_doc_writer);
 
-        self.raw_pdf_doc.save(&mut buf_writer).unwrap();
+        if self.alphas.is_empty()
+            && self.separations.is_empty()
+            && self.patterns.is_empty()
+            && self.images.is_empty()
+            && self.output_intent.is_none()
+        {
+            self.raw_pdf_doc.save(&mut buf_writer).unwrap();
+        } else {
+            let mut raw_pdf = BufWriter::new(vec![]);
+            self.raw_pdf_doc.save(&mut raw_pdf).unwrap();
+
+            let raw_pdf = raw_pdf
+                .into_inner()
+                .map_err(|e| InternalServerError::WritePdfError(e.into()))?;
+
+            self.finish_pdf(&raw_pdf, &mut buf_writer).map_err(|e| {
+                InternalServerError::FinishPdfError {
+                    source: Box::new(e),
+                }
+            })?;
+        }
 
         let write_result = buf_writer
             .into_inner()
@@ -117,6 +188,30 @@ impl<'a> PrintPdfWriter<'a> {
 
         Ok(write_result?)
     }
+
+    /// Adds what printpdf can't write itself to the PDF it wrote
+    fn finish_pdf<W: Write>(
+        &self,
+        raw_pdf: &[u8],
+        pdf_doc_writer: &mut W,
+    ) -> Result<(), printpdf::lopdf::Error> {
+        let mut document = printpdf::lopdf::Document::load_mem(raw_pdf)?;
+
+        // Before the transparency groups take a copy of the page's resources
+        color_spaces::add_separations(&mut document, &self.separations)?;
+        gradients::add_patterns(&mut document, &self.patterns)?;
+        background_image::add_images(&mut document, &self.images)?;
+        transparency::apply_transparency(&mut document, &self.alphas)?;
+
+        if let Some(output_intent) = &self.output_intent {
+            output_intent.apply(&mut document)?;
+        }
+
+        document.compress();
+        document.save_to(pdf_doc_writer)?;
+
+        Ok(())
+    }
 }
 
 impl<'a> UnstructuredDocumentWriter for PrintPdfWriter<'a> {
@@ -126,10 +221,6 @@ impl<'a> UnstructuredDocumentWriter for PrintPdfWriter<'a> {
         style: &Style::Unmergeable,
         text_block: &RenderedTextBlock,
     ) -> Result<&mut Self, DocumentGenerationError> {
-        let layer = self.get_base_layer(node.page_index);
-
-        layer.begin_text_section();
-
         let x =
             printpdf::Pt::from(style.padding.left + node.page_layout.left + self.page_margins.left);
         let y = printpdf::Pt::from(
@@ -137,23 +228,95 @@ impl<'a> UnstructuredDocumentWriter for PrintPdfWriter<'a> {
                 - (node.page_layout.top + style.padding.top + self.page_margins.top),
         );
 
+        self.draw_span_backgrounds(node.page_index, Pt(x.0), Pt(y.0), text_block);
+
+        let layer = self.get_base_layer(node.page_index);
+
+        // The text's color and alpha are set for the section alone, so that
+        // the shapes drawn after it start out opaque again
+        let outer_style = self.current_style_by_page[node.page_index].clone();
+        layer.save_graphics_state();
+        layer.begin_text_section();
+
         let mut current_y = y;
         for line in text_block.lines.iter() {
+            let baseline = current_y - line.line_metrics.ascent.into();
+
             layer.set_text_matrix(TextMatrix::Translate(
                 x + line.line_metrics.left.into(),
-                current_y - line.line_metrics.ascent.into(),
+                baseline,
             ));
 
-            for span in line.rich_text.0.iter() {
+            let mut in_whitespace = false;
+            // Whether the text cursor has strayed from where skia laid out the
+            // text, after a shifted span or a spacer
+            let mut is_displaced = false;
+
+            for (span_index, span) in line.rich_text.0.iter().enumerate() {
+                // Spacers only reserve room in the layout
+                if span.is_spacer() {
+                    is_displaced = true;
+                    continue;
+                }
+
                 let font = self.set_base_layer_style(node.page_index, &layer, span)?;
 
-                layer.write_text(span.text.clone(), font.as_ref());
+                // Shifted spans, and the first visible one after the cursor
+                // strays, start where skia placed them
+                let is_shifted = span.baseline_shift != Pt(0.);
+                let run = line.runs.get(span_index).filter(|run| run.width > Pt(0.));
+
+                match run {
+                    Some(run) if is_shifted || is_displaced => {
+                        layer.set_text_matrix(TextMatrix::Translate(
+                            x + run.left.into(),
+                            baseline + span.baseline_shift.into(),
+                        ));
+                        is_displaced = is_shifted;
+                    }
+                    _ => is_displaced |= is_shifted,
+                }
+
+                let small_caps = if span.small_caps {
+                    self.font_collection
+                        .lookup_font(&span.font_family, &span.attributes)?
+                        .small_caps()
+                } else {
+                    None
+                };
+
+                if let Some(small_caps) = small_caps {
+                    write_small_caps(
+                        &layer,
+                        span,
+                        small_caps,
+                        line.word_spacing,
+                        &mut in_whitespace,
+                    );
+                    // The cursor ends where the widths the PDF has for the
+                    // glyphs put it, not after the last glyph's own advance
+                    is_displaced = true;
+                } else if line.word_spacing == Pt(0.) {
+                    layer.write_text(span.text.clone(), font.as_ref());
+                } else {
+                    write_justified_text(
+                        &layer,
+                        span,
+                        font.as_ref(),
+                        line.word_spacing,
+                        &mut in_whitespace,
+                    );
+                }
             }
 
             current_y -= line.line_metrics.height.into();
         }
 
         layer.end_text_section();
+        layer.restore_graphics_state();
+        self.current_style_by_page[node.page_index] = outer_style;
+
+        self.draw_text_decorations(node.page_index, Pt(x.0), Pt(y.0), text_block);
 
         Ok(self)
     }
@@ -161,6 +324,8 @@ impl<'a> UnstructuredDocumentWriter for PrintPdfWriter<'a> {
     fn draw_node(&mut self, node: &PaginatedNode) -> Result<&mut Self, DocumentGenerationError> {
         let node_style = node.drawable_node.style();
 
+        self.enter_groups(node.page_index, &node.groups);
+
         self.draw_container(node, node_style)?;
 
         // Remove this allow once we have image rendering
@@ -189,14 +354,25 @@ impl<'a> PrintPdfWriter<'a> {
             height: node.page_layout.height,
         };
 
-        self.draw_rect(
+        // Outer shadows are beneath the background, and inset ones are within
+        // the border, so neither is drawn over by the rest of the box
+        if !container_style.box_shadow.inset {
+            self.draw_box_shadow(node.page_index, &rect, container_style);
+        }
+
+        self.draw_background(
             node.page_index,
-            rect,
-            container_style.border.width.clone(),
-            Some(container_style.border.color.clone()),
+            &rect,
+            &container_style.border,
             container_style.background_color.clone(),
-            Some(container_style.border.radius.clone()),
+            container_style.background.as_ref(),
         );
+        self.draw_background_image(node.page_index, &rect, container_style)?;
+        self.draw_border(node.page_index, &rect, &container_style.border);
+
+        if container_style.box_shadow.inset {
+            self.draw_box_shadow(node.page_index, &rect, container_style);
+        }
 
         if container_style.debug {
             self.draw_debug_outlines(node, container_style);
@@ -237,7 +413,7 @@ impl<'a> PrintPdfWriter<'a> {
             .font_collection
             .lookup_font(&span.font_family, &span.attributes)?;
 
-        let current_style = &self.current_style_by_page[page_index];
+        let current_style = self.current_style_by_page[page_index].clone();
 
         let font_ref = self.get_font(font.font_id())?;
 
@@ -259,7 +435,12 @@ impl<'a> PrintPdfWriter<'a> {
         }
 
         if current_style.color.as_ref() != Some(&span.color) {
-            layer.set_fill_color(span.color.clone().into());
+            self.set_fill_color(layer, &span.color);
+
+            let current_alpha = current_style.color.as_ref().map_or(1., |color| color.a);
+            if span.color.a != current_alpha {
+                self.set_fill_alpha(layer, span.color.a);
+            }
 
             new_style.color = Some(span.color.clone());
         }
@@ -269,3 +450,202 @@ impl<'a> PrintPdfWriter<'a> {
         Ok(font_ref)
     }
 }
+
+/// Writes `span`, widening the first character of every run of whitespace by
+/// `word_spacing`. This uses character spacing because word spacing only
+/// applies to single byte encodings, not the embedded fonts we write.
+fn write_justified_text(
+    layer: &PdfLayerReference,
+    span: &RichTextSpan,
+    font: &IndirectFontRef,
+    word_spacing: Pt,
+    in_whitespace: &mut bool,
+) {
+    let mut segment_start = 0;
+
+    for (index, char) in span.text.char_indices() {
+        let starts_patch = char.is_whitespace() && !*in_whitespace;
+        *in_whitespace = char.is_whitespace();
+
+        if starts_patch {
+            let patch_end = index + char.len_utf8();
+
+            if segment_start < index {
+                layer.write_text(&span.text[segment_start..index], font);
+            }
+
+            layer.set_character_spacing((span.letter_spacing + word_spacing).0);
+            layer.write_text(&span.text[index..patch_end], font);
+            layer.set_character_spacing(span.letter_spacing.0);
+
+            segment_start = patch_end;
+        }
+    }
+
+    if segment_start < span.text.len() {
+        layer.write_text(&span.text[segment_start..], font);
+    }
+}
+
+/// Writes `span` by glyph id, with the font's small capitals in place of the
+/// lower case letters that have them. The small capitals have no codepoint,
+/// so the PDF only knows them by the default width, and each glyph is moved
+/// back by how much narrower than that the one before it is. Whitespace is
+/// widened by `word_spacing` the same way as `write_justified_text` does.
+fn write_small_caps(
+    layer: &PdfLayerReference,
+    span: &RichTextSpan,
+    small_caps: &SmallCaps,
+    word_spacing: Pt,
+    in_whitespace: &mut bool,
+) {
+    // Offsets between glyphs are in thousandths of the font size
+    let word_spacing = word_spacing.0 * 1000. / span.size.0;
+    let mut offset = 0.;
+
+    let glyphs: Vec<(i64, u16)> = span
+        .text
+        .chars()
+        .zip(small_caps.glyphs(&span.text))
+        .map(|(char, (glyph, advance))| {
+            let positioned = (offset.round() as i64, glyph);
+
+            let starts_patch = char.is_whitespace() && !*in_whitespace;
+            *in_whitespace = char.is_whitespace();
+
+            offset = DEFAULT_GLYPH_WIDTH - advance;
+            if starts_patch {
+                offset -= word_spacing;
+            }
+
+            positioned
+        })
+        .collect();
+
+    layer.write_positioned_codepoints(glyphs);
+}
+
+#[cfg(test)]
+mod tests {
+    use printpdf::lopdf;
+    use serde_json::json;
+
+    use crate::{
+        block_layout::{
+            layout_engine::NodeLayout,
+            paginated_layout::{DrawableContainerNode, DrawableTextNode},
+        },
+        doc_structure::FontFamilyInfo,
+        load_fonts_from_doc_structure, page_sizes,
+        paragraph_layout::{LineMetrics, RenderedTextLine},
+        rich_text::RichText,
+    };
+
+    use super::*;
+
+    fn page_operators(pdf: &[u8]) -> Vec<String> {
+        let document = lopdf::Document::load_mem(pdf).unwrap();
+        let page_id = *document.get_pages().values().next().unwrap();
+
+        document
+            .get_and_decode_page_content(page_id)
+            .unwrap()
+            .operations
+            .into_iter()
+            .map(|operation| operation.operator)
+            .collect()
+    }
+
+    #[test]
+    fn resets_the_alpha_of_translucent_text() {
+        let fonts: Vec<FontFamilyInfo> = serde_json::from_value(json!([{
+            "familyName": "Inter",
+            "fonts": [{
+                "source": concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../../assets/fonts/inter-static/Inter-Regular.ttf"
+                ),
+                "weight": "Regular",
+            }],
+        }]))
+        .unwrap();
+        let font_collection = load_fonts_from_doc_structure(&fonts).unwrap();
+
+        let mut writer = PrintPdfWriter::new(
+            "Translucent text",
+            page_sizes::LETTER,
+            EdgeStyle::Unmergeable::new(Pt(0.)),
+            &font_collection,
+        );
+
+        let layout = NodeLayout {
+            width: Pt(100.),
+            height: Pt(20.),
+            ..Default::default()
+        };
+
+        let faded_span = RichTextSpan {
+            text: String::from("Faded"),
+            font_family: String::from("Inter"),
+            size: Pt(12.),
+            color: Color {
+                a: 0.5,
+                ..Color::black()
+            },
+            ..Default::default()
+        };
+        let text_block = RenderedTextBlock {
+            lines: vec![RenderedTextLine {
+                rich_text: RichText(vec![faded_span]),
+                line_metrics: LineMetrics {
+                    ascent: Pt(12.),
+                    descent: Pt(3.),
+                    baseline: Pt(12.),
+                    height: Pt(15.),
+                    width: Pt(30.),
+                    left: Pt(0.),
+                },
+                word_spacing: Pt(0.),
+                runs: vec![],
+            }],
+        };
+
+        writer
+            .draw_node(&PaginatedNode {
+                page_layout: layout.clone(),
+                page_index: 0,
+                drawable_node: DrawableNode::Text(DrawableTextNode {
+                    text_block,
+                    style: Default::default(),
+                }),
+                groups: vec![],
+            })
+            .unwrap();
+
+        let mut opaque_style = Style::Unmergeable::default();
+        opaque_style.background_color = Some(Color::rgb(1., 0., 0.));
+
+        writer
+            .draw_node(&PaginatedNode {
+                page_layout: layout,
+                page_index: 0,
+                drawable_node: DrawableNode::Container(DrawableContainerNode {
+                    style: opaque_style,
+                }),
+                groups: vec![],
+            })
+            .unwrap();
+
+        let operators = page_operators(&writer.save(vec![]).unwrap());
+        let text_start = operators.iter().position(|op| op == "BT").unwrap();
+        let text_end = operators.iter().position(|op| op == "ET").unwrap();
+
+        // The text's alpha is set within the section's graphics state, which
+        // is restored before the opaque background is filled
+        assert_eq!(operators[text_start - 1], "q");
+        assert!(operators[text_start..text_end].contains(&String::from("gs")));
+        assert_eq!(operators[text_end + 1], "Q");
+        assert!(!operators[text_end..].contains(&String::from("gs")));
+        assert!(operators[text_end..].contains(&String::from("f")));
+    }
+}
@@ -0,0 +1,92 @@
+//! The output intent, which tells a printer what the document's colors are
+//!  meant to be printed with.
+
+use bytes::Bytes;
+use printpdf::lopdf::{Dictionary, Document, Object, Stream, StringFormat};
+
+#[derive(Clone, Debug)]
+pub struct OutputIntent {
+    /// The ICC profile of the printing condition
+    profile: Bytes,
+    /// How many components the profile's colors have
+    components: i64,
+    /// The name of the printing condition, e.g. `FOGRA39`
+    identifier: String,
+    info: Option<String>,
+}
+
+impl OutputIntent {
+    /// None if `profile` isn't a CMYK or grayscale ICC profile, which are the
+    /// only ones a PDF/X output intent can describe
+    pub fn new(profile: Bytes, identifier: String, info: Option<String>) -> Option<Self> {
+        // The header gives the profile's color space at bytes 16 to 20, and
+        // its signature at bytes 36 to 40
+        if profile.get(36..40) != Some(b"acsp") {
+            return None;
+        }
+
+        let components = match profile.get(16..20)? {
+            b"GRAY" => 1,
+            b"CMYK" => 4,
+            _ => return None,
+        };
+
+        Some(Self {
+            profile,
+            components,
+            identifier,
+            info,
+        })
+    }
+
+    /// Embeds the profile in `document`, in place of any output intent it
+    /// already has
+    pub(super) fn apply(&self, document: &mut Document) -> Result<(), printpdf::lopdf::Error> {
+        let mut profile_dict = Dictionary::new();
+        profile_dict.set("N", Object::Integer(self.components));
+
+        let profile_id = document.add_object(Stream::new(profile_dict, self.profile.to_vec()));
+
+        let text = |text: &str| Object::String(text.as_bytes().to_vec(), StringFormat::Literal);
+
+        let mut output_intent = Dictionary::new();
+        output_intent.set("Type", Object::Name(b"OutputIntent".to_vec()));
+        output_intent.set("S", Object::Name(b"GTS_PDFX".to_vec()));
+        output_intent.set("OutputConditionIdentifier", text(&self.identifier));
+        if let Some(info) = &self.info {
+            output_intent.set("Info", text(info));
+        }
+        output_intent.set("DestOutputProfile", Object::Reference(profile_id));
+
+        let catalog_id = document.trailer.get(b"Root")?.as_reference()?;
+        document
+            .get_object_mut(catalog_id)?
+            .as_dict_mut()?
+            .set("OutputIntents", vec![Object::Dictionary(output_intent)]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile_header(color_space: &[u8; 4]) -> Bytes {
+        let mut header = vec![0; 128];
+        header[16..20].copy_from_slice(color_space);
+        header[36..40].copy_from_slice(b"acsp");
+
+        Bytes::from(header)
+    }
+
+    #[test]
+    fn only_accepts_print_profiles() {
+        let new = |color_space| OutputIntent::new(profile_header(color_space), String::new(), None);
+
+        assert_eq!(new(b"CMYK").map(|intent| intent.components), Some(4));
+        assert_eq!(new(b"GRAY").map(|intent| intent.components), Some(1));
+        assert!(new(b"RGB ").is_none());
+        assert!(OutputIntent::new(Bytes::from(vec![0; 128]), String::new(), None).is_none());
+    }
+}
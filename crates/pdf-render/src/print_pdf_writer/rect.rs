@@ -1,229 +1,209 @@
-use printpdf::{Line, PdfLayerReference, Point};
+use printpdf::{lopdf::content::Operation, Line, LineCapStyle, LineDashPattern, PdfLayerReference};
 
 use crate::{
-    stylesheet::{BorderRadiusStyle, EdgeStyle},
-    values::{Color, Pt, Rect},
+    stylesheet::{BorderLineStyle, BorderStyle},
+    values::{Color, Gradient, Pt, Rect},
 };
 
-use super::PrintPdfWriter;
+use super::{
+    corners::{add_polygon, clip, RoundedRect},
+    PrintPdfWriter,
+};
 
 impl<'a> PrintPdfWriter<'a> {
     pub(super) fn draw_rect(
         &mut self,
         page_index: usize,
         rect: Rect<Pt>,
-        border_width: EdgeStyle::Unmergeable,
-        border_color: Option<Color>,
+        border: &BorderStyle::Unmergeable,
         background_color: Option<Color>,
-        border_radius: Option<BorderRadiusStyle::Unmergeable>,
+        background_gradient: Option<&Gradient>,
     ) {
-        let layer = self.get_base_layer(page_index);
-
-        let start = Point {
-            x: rect.left.into(),
-            y: rect.top.into(),
-        };
-
-        let end = Point {
-            x: (rect.left + rect.width).into(),
-            y: (rect.top - rect.height).into(),
-        };
+        self.draw_background(
+            page_index,
+            &rect,
+            border,
+            background_color,
+            background_gradient,
+        );
+        self.draw_border(page_index, &rect, border);
+    }
 
-        let mut edge_ranges = [0..1, 1..2, 2..3, 3..4];
-        // let mut top_range = 0..1;
-        // let mut right_range = 1..2;
-        // let mut bottom_range = 2..3;
-        // let mut left_range = 3..4;
-
-        let corners = &[
-            Point {
-                x: start.x,
-                y: start.y,
-            },
-            Point {
-                x: end.x,
-                y: start.y,
-            },
-            Point { x: end.x, y: end.y },
-            Point {
-                x: start.x,
-                y: end.y,
-            },
-        ];
+    /// Fills the box within the corners of its border, beneath the border
+    /// itself
+    pub(super) fn draw_background(
+        &mut self,
+        page_index: usize,
+        rect: &Rect<Pt>,
+        border: &BorderStyle::Unmergeable,
+        background_color: Option<Color>,
+        background_gradient: Option<&Gradient>,
+    ) {
+        let layer = self.get_base_layer(page_index);
 
-        // let offsets = &[(1., -1.), (-1., -1.), (-1., 1.), (1., 1.)];
-
-        let lines = &[
-            (
-                Point {
-                    x: start.x,
-                    y: start.y,
-                },
-                false,
-            ),
-            (
-                Point {
-                    x: end.x,
-                    y: start.y,
-                },
-                false,
-            ),
-            (Point { x: end.x, y: end.y }, false),
-            (
-                Point {
-                    x: start.x,
-                    y: end.y,
-                },
-                false,
-            ),
-        ];
+        let outer = RoundedRect::new(rect, &border.radius);
 
-        let points = match border_radius {
-            Some(border_radius) if border_radius != BorderRadiusStyle::Unmergeable::default() => {
-                // 4 points per corner & 2 points per edge
-                let mut points: Vec<(printpdf::Point, bool)> = Vec::with_capacity(4 * 4 + 4 * 2);
-
-                let radii = &[
-                    border_radius.top_left.0,
-                    border_radius.top_right.0,
-                    border_radius.bottom_right.0,
-                    border_radius.bottom_left.0,
-                ];
-
-                let circle_corners: Vec<_> = radii
-                    .iter()
-                    .map(|&r| self.circle_cache.get(Pt(r)))
-                    .collect();
-
-                let corner_points = &[
-                    circle_corners[0].top_left(),
-                    circle_corners[1].top_right(),
-                    circle_corners[2].bottom_right(),
-                    circle_corners[3].bottom_left(),
-                ];
-
-                for edge_idx in 0..4 {
-                    let current_corner = corner_points[edge_idx];
-
-                    let first_point = current_corner.first().map(|(pt, _)| pt).unwrap();
-                    let last_point = current_corner.last().map(|(pt, _)| pt).unwrap();
-
-                    let circle_offset = Point {
-                        x: first_point.x + last_point.x,
-                        y: first_point.y + last_point.y,
-                    };
-
-                    let start_idx = points.len();
-
-                    points.extend(current_corner.iter().map(|&(pt, is_curve)| {
-                        (
-                            printpdf::Point {
-                                x: pt.x + corners[edge_idx].x - circle_offset.x,
-                                y: pt.y + corners[edge_idx].y - circle_offset.y,
-                            },
-                            is_curve,
-                        )
-                    }));
-
-                    edge_ranges[edge_idx] = start_idx..points.len();
-                }
+        if let Some(background_color) = background_color {
+            layer.save_graphics_state();
 
-                points
+            if background_color.a < 1. {
+                self.set_fill_alpha(&layer, background_color.a);
             }
-            _ => lines.to_vec(),
-        };
-
-        layer.save_graphics_state();
-
-        if let Some(background_color) = background_color {
-            let line = Line {
-                points: points.clone(),
+            self.set_fill_color(&layer, &background_color);
+            layer.add_shape(Line {
+                points: outer.outline(),
                 is_closed: true,
                 has_fill: true,
                 has_stroke: false,
                 is_clipping_path: false,
-            };
+            });
 
-            layer.set_outline_thickness(1.0);
-            // layer.set_outline_color(Color::black().into());
-            layer.set_fill_color(background_color.into());
-            layer.add_shape(line);
+            layer.restore_graphics_state();
         }
 
-        if let Some(color) = border_color {
-            layer.set_outline_color(color.into());
-
-            draw_border_edge(
-                &layer,
-                points[edge_ranges[0].start..edge_ranges[1].end].iter(),
-                border_width.top,
-                Point {
-                    x: printpdf::Pt(0.),
-                    y: printpdf::Pt(border_width.top.0 / -2.),
-                },
-            );
-            draw_border_edge(
-                &layer,
-                points[edge_ranges[1].start..edge_ranges[2].end].iter(),
-                border_width.right,
-                Point {
-                    x: printpdf::Pt(border_width.right.0 / -2.),
-                    y: printpdf::Pt(0.),
-                },
-            );
-            draw_border_edge(
-                &layer,
-                points[edge_ranges[2].start..edge_ranges[3].end].iter(),
-                border_width.bottom,
-                Point {
-                    x: printpdf::Pt(0.),
-                    y: printpdf::Pt(border_width.bottom.0 / 2.),
-                },
-            );
-            draw_border_edge(
-                &layer,
-                points[edge_ranges[3].start..]
-                    .iter()
-                    .chain(points[..edge_ranges[0].end].iter()),
-                border_width.left,
-                Point {
-                    x: printpdf::Pt(border_width.left.0 / 2.),
-                    y: printpdf::Pt(0.),
-                },
-            );
+        if let Some(gradient) = background_gradient {
+            layer.save_graphics_state();
+            self.fill_gradient(&layer, rect, outer.outline(), gradient);
+            layer.restore_graphics_state();
+        }
+    }
+
+    /// Fills the ring between the outside of the border and the rectangle
+    /// inside its widths, like browsers do, so that sides of different widths meet along
+    /// the line between their outer and inner corners
+    pub(super) fn draw_border(
+        &mut self,
+        page_index: usize,
+        rect: &Rect<Pt>,
+        border: &BorderStyle::Unmergeable,
+    ) {
+        let layer = self.get_base_layer(page_index);
+        let outer = RoundedRect::new(rect, &border.radius);
+
+        let widths = [
+            border.width.top.0.max(0.),
+            border.width.right.0.max(0.),
+            border.width.bottom.0.max(0.),
+            border.width.left.0.max(0.),
+        ];
+        let sides = [
+            (&border.color.top, border.style.top),
+            (&border.color.right, border.style.right),
+            (&border.color.bottom, border.style.bottom),
+            (&border.color.left, border.style.left),
+        ];
+
+        let drawn_sides: Vec<usize> = (0..4).filter(|&index| widths[index] > 0.).collect();
+
+        let (first_color, first_style) = match drawn_sides.first() {
+            Some(&index) => sides[index],
+            None => return,
+        };
+
+        // Sides that look the same are filled at once, so that there's no seam
+        // where they meet
+        let is_uniform = matches!(
+            first_style,
+            BorderLineStyle::Solid | BorderLineStyle::Double
+        ) && drawn_sides
+            .iter()
+            .all(|&index| sides[index] == (first_color, first_style));
+
+        if is_uniform {
+            layer.save_graphics_state();
+            self.draw_border_sides(&layer, &outer, widths, first_color, first_style, 0.);
+            layer.restore_graphics_state();
+
+            return;
         }
 
-        layer.restore_graphics_state();
+        let inner = outer.inset(widths[0], widths[1], widths[2], widths[3]);
+
+        for index in drawn_sides {
+            let (color, line_style) = sides[index];
+
+            layer.save_graphics_state();
+
+            let region = outer.side_region(&inner, index);
+            add_polygon(&layer, &region);
+            clip(&layer, "W");
+
+            self.draw_border_sides(&layer, &outer, widths, color, line_style, widths[index]);
+
+            layer.restore_graphics_state();
+        }
     }
-}
 
-fn draw_border_edge<'a>(
-    layer: &PdfLayerReference,
-    points: impl Iterator<Item = &'a (printpdf::Point, bool)>,
-    width: Pt,
-    offset: Point,
-) {
-    if width != Pt(0.) {
-        layer.set_outline_thickness(width.0);
-        let line = Line {
-            points: points
-                .cloned()
-                .map(|(pt, is_curve)| {
-                    (
-                        Point {
-                            x: pt.x + offset.x,
-                            y: pt.y + offset.y,
-                        },
-                        is_curve,
-                    )
-                })
-                .collect(),
-            is_closed: false,
-            has_fill: false,
-            has_stroke: true,
-            is_clipping_path: false,
+    /// Draws the whole ring in one style, for sides that are either drawn
+    /// together or clipped to their own part of it. Dashes and dots are
+    /// `stroke_width` thick.
+    fn draw_border_sides(
+        &mut self,
+        layer: &PdfLayerReference,
+        outer: &RoundedRect,
+        [top, right, bottom, left]: [f64; 4],
+        color: &Color,
+        line_style: BorderLineStyle,
+        stroke_width: f64,
+    ) {
+        let inset = |fraction: f64| {
+            outer.inset(
+                top * fraction,
+                right * fraction,
+                bottom * fraction,
+                left * fraction,
+            )
         };
 
-        layer.add_shape(line);
+        match line_style {
+            BorderLineStyle::Solid | BorderLineStyle::Double => {
+                if color.a < 1. {
+                    self.set_fill_alpha(layer, color.a);
+                }
+                self.set_fill_color(layer, color);
+
+                // Two rings, each a third of the width
+                let rings = match line_style {
+                    BorderLineStyle::Double => vec![(0., 1. / 3.), (2. / 3., 1.)],
+                    _ => vec![(0., 1.)],
+                };
+
+                for (outer_fraction, inner_fraction) in rings {
+                    inset(outer_fraction).add_subpath(layer);
+                    inset(inner_fraction).add_subpath(layer);
+                }
+
+                layer.add_operation(Operation::new("f*", vec![]));
+            }
+            BorderLineStyle::Dashed | BorderLineStyle::Dotted => {
+                if color.a < 1. {
+                    self.set_stroke_alpha(layer, color.a);
+                }
+                self.set_outline_color(layer, color);
+
+                let dash = |dash: f64, gap: f64| LineDashPattern {
+                    dash_1: Some(dash.round() as i64),
+                    gap_1: Some(gap.round().max(1.) as i64),
+                    ..Default::default()
+                };
+
+                // Zero length dashes with round caps are drawn as dots
+                let (dash_pattern, line_cap) = match line_style {
+                    BorderLineStyle::Dotted => (dash(0., stroke_width * 2.), LineCapStyle::Round),
+                    _ => (
+                        dash((stroke_width * 3.).max(1.), stroke_width * 3.),
+                        LineCapStyle::Butt,
+                    ),
+                };
+
+                layer.set_line_dash_pattern(dash_pattern);
+                layer.set_line_cap_style(line_cap);
+                layer.set_outline_thickness(stroke_width);
+
+                // Along the middle of the ring
+                inset(0.5).add_subpath(layer);
+                layer.add_operation(Operation::new("S", vec![]));
+            }
+        }
     }
 }
@@ -0,0 +1,29 @@
+use printpdf::lopdf::{Dictionary, Document, Object};
+
+/// Adds `objects`, by name, to the `category` resources of every page of
+/// `document`
+pub(super) fn add_page_resources(
+    document: &mut Document,
+    category: &str,
+    objects: &[(String, Object)],
+) -> Result<(), printpdf::lopdf::Error> {
+    if objects.is_empty() {
+        return Ok(());
+    }
+
+    for page_id in document.get_pages().into_values() {
+        let resources = document.get_or_create_resources(page_id)?.as_dict_mut()?;
+
+        if !resources.has(category.as_bytes()) {
+            resources.set(category, Dictionary::new());
+        }
+
+        let named_objects = resources.get_mut(category.as_bytes())?.as_dict_mut()?;
+
+        for (name, object) in objects {
+            named_objects.set(name.clone(), object.clone());
+        }
+    }
+
+    Ok(())
+}
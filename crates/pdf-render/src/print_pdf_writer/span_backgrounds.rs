@@ -0,0 +1,107 @@
+use crate::{
+    paragraph_layout::{RenderedTextBlock, RunMetrics},
+    rich_text::{span_background::SpanBackground, RichTextSpan},
+    stylesheet::{BorderRadiusStyle, BorderStyle, CornerRadius},
+    values::{Pt, Rect},
+};
+
+use super::PrintPdfWriter;
+
+impl<'a> PrintPdfWriter<'a> {
+    /// Fills the backgrounds of the nested spans in `text_block`, whose first
+    /// line starts at (`left`, `top`). A background that wraps onto several
+    /// lines (or pages) gets a box per line, and only the boxes at the ends of
+    /// the span have its rounded corners.
+    pub(super) fn draw_span_backgrounds(
+        &mut self,
+        page_index: usize,
+        left: Pt,
+        top: Pt,
+        text_block: &RenderedTextBlock,
+    ) {
+        let mut current_y = top;
+        for line in text_block.lines.iter() {
+            let baseline = current_y - line.line_metrics.ascent;
+            let runs: Vec<_> = line.rich_text.0.iter().zip(line.runs.iter()).collect();
+
+            let mut fragment_start = 0;
+            while let Some((first_span, _)) = runs.get(fragment_start) {
+                let fragment_length = runs[fragment_start..]
+                    .iter()
+                    .take_while(|(span, _)| span.background == first_span.background)
+                    .count();
+                let fragment = &runs[fragment_start..fragment_start + fragment_length];
+
+                if let Some(background) = &first_span.background {
+                    self.draw_span_background(page_index, background, fragment, left, baseline);
+                }
+
+                fragment_start += fragment_length;
+            }
+
+            current_y -= line.line_metrics.height;
+        }
+    }
+
+    /// Fills the box around the glyphs of `fragment`, the spans of one line
+    /// that share `background`
+    fn draw_span_background(
+        &mut self,
+        page_index: usize,
+        background: &SpanBackground,
+        fragment: &[(&RichTextSpan, &RunMetrics)],
+        left: Pt,
+        baseline: Pt,
+    ) {
+        let drawn_runs = || fragment.iter().filter(|(_, run)| run.width > Pt(0.));
+
+        let (run_left, run_right) = match (
+            drawn_runs().map(|(_, run)| run.left.0).reduce(f64::min),
+            drawn_runs()
+                .map(|(_, run)| (run.left + run.width).0)
+                .reduce(f64::max),
+        ) {
+            (Some(run_left), Some(run_right)) => (run_left, run_right),
+            _ => return,
+        };
+
+        let glyphs_top = drawn_runs()
+            .map(|(span, run)| (baseline + span.baseline_shift + run.ascent).0)
+            .fold(f64::MIN, f64::max);
+        let glyphs_bottom = drawn_runs()
+            .map(|(span, run)| (baseline + span.baseline_shift - run.descent).0)
+            .fold(f64::MAX, f64::min);
+
+        // The spacers that reserve the horizontal padding sit at the ends of
+        // the span, so the box already covers it
+        let top = Pt(glyphs_top) + background.padding.top;
+        let bottom = Pt(glyphs_bottom) - background.padding.bottom;
+
+        let starts = fragment.first().map(|(span, _)| span.is_spacer()) == Some(true);
+        let ends = fragment.last().map(|(span, _)| span.is_spacer()) == Some(true);
+        let radius = &background.radius;
+        let start_radius = |radius: CornerRadius| if starts { radius } else { Default::default() };
+        let end_radius = |radius: CornerRadius| if ends { radius } else { Default::default() };
+
+        self.draw_rect(
+            page_index,
+            Rect {
+                left: left + Pt(run_left),
+                top,
+                width: Pt(run_right - run_left),
+                height: top - bottom,
+            },
+            &BorderStyle::Unmergeable {
+                radius: BorderRadiusStyle::Unmergeable {
+                    top_left: start_radius(radius.top_left),
+                    bottom_left: start_radius(radius.bottom_left),
+                    top_right: end_radius(radius.top_right),
+                    bottom_right: end_radius(radius.bottom_right),
+                },
+                ..Default::default()
+            },
+            Some(background.color.clone()),
+            None,
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Constant alpha for colors that aren't opaque, and transparency groups for
+//!  nodes with an `opacity`.
+//!
+//! printpdf can't add graphics states of our own to a page, so the content
+//!  refers to them by name and they're added to the written PDF afterwards.
+//!  Groups are written as marked content, which is then moved into a form
+//!  XObject that's painted with the group's opacity.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use printpdf::{
+    lopdf::{
+        content::{Content, Operation},
+        Dictionary, Document, Object, ObjectId, Stream,
+    },
+    PdfLayerReference,
+};
+
+use super::PrintPdfWriter;
+
+/// Marks the content of a group, followed by its opacity in thousandths
+const GROUP_TAG: &str = "OpacityGroup";
+
+/// A constant alpha, in thousandths
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum Alpha {
+    Fill(u16),
+    Stroke(u16),
+    /// Both, for painting a group
+    Group(u16),
+}
+
+impl Alpha {
+    fn name(&self) -> String {
+        match self {
+            Self::Fill(alpha) => format!("FillAlpha{}", alpha),
+            Self::Stroke(alpha) => format!("StrokeAlpha{}", alpha),
+            Self::Group(alpha) => format!("GroupAlpha{}", alpha),
+        }
+    }
+
+    fn graphics_state(&self) -> Dictionary {
+        let mut graphics_state = Dictionary::new();
+        graphics_state.set("Type", Object::Name(b"ExtGState".to_vec()));
+
+        match *self {
+            Self::Fill(alpha) => graphics_state.set("ca", Object::Real(f64::from(alpha) / 1000.)),
+            Self::Stroke(alpha) => graphics_state.set("CA", Object::Real(f64::from(alpha) / 1000.)),
+            Self::Group(alpha) => {
+                graphics_state.set("ca", Object::Real(f64::from(alpha) / 1000.));
+                graphics_state.set("CA", Object::Real(f64::from(alpha) / 1000.));
+            }
+        }
+
+        graphics_state
+    }
+}
+
+fn thousandths(alpha: f64) -> u16 {
+    (alpha.clamp(0., 1.) * 1000.).round() as u16
+}
+
+impl<'a> PrintPdfWriter<'a> {
+    pub(super) fn set_fill_alpha(&mut self, layer: &PdfLayerReference, alpha: f64) {
+        self.set_alpha(layer, Alpha::Fill(thousandths(alpha)));
+    }
+
+    pub(super) fn set_stroke_alpha(&mut self, layer: &PdfLayerReference, alpha: f64) {
+        self.set_alpha(layer, Alpha::Stroke(thousandths(alpha)));
+    }
+
+    fn set_alpha(&mut self, layer: &PdfLayerReference, alpha: Alpha) {
+        layer.add_operation(Operation::new(
+            "gs",
+            vec![Object::Name(alpha.name().into_bytes())],
+        ));
+
+        self.alphas.insert(alpha);
+    }
+
+    /// Starts the marked content of a group, which is painted with
+    /// `opacity` once it's ended
+    pub(super) fn begin_opacity_group(&mut self, layer: &PdfLayerReference, opacity: f64) {
+        let opacity = thousandths(opacity);
+        let tag = format!("{}{}", GROUP_TAG, opacity);
+
+        layer.add_operation(Operation::new("BMC", vec![Object::Name(tag.into_bytes())]));
+        self.alphas.insert(Alpha::Group(opacity));
+    }
+}
+
+/// Adds the graphics states in `alphas`, which are all the ones the content
+/// refers to, to every page of `document`, and turns the marked groups into
+/// form XObjects.
+pub(super) fn apply_transparency(
+    document: &mut Document,
+    alphas: &BTreeSet<Alpha>,
+) -> Result<(), printpdf::lopdf::Error> {
+    let mut page_forms: Vec<(ObjectId, Vec<ObjectId>)> = vec![];
+
+    for page_id in document.get_pages().into_values() {
+        let content = Content::decode(&document.get_page_content(page_id)?)?;
+
+        let mut groups = vec![];
+        let operations = extract_groups(content.operations, &mut groups);
+
+        if groups.is_empty() {
+            page_forms.push((page_id, vec![]));
+            continue;
+        }
+
+        document.change_page_content(page_id, Content { operations }.encode()?)?;
+
+        let bounding_box = document.get_dictionary(page_id)?.get(b"MediaBox")?.clone();
+        let mut form_ids = vec![];
+
+        for (index, (_, operations)) in groups.into_iter().enumerate() {
+            let mut group = Dictionary::new();
+            group.set("Type", Object::Name(b"Group".to_vec()));
+            group.set("S", Object::Name(b"Transparency".to_vec()));
+
+            let mut form = Dictionary::new();
+            form.set("Type", Object::Name(b"XObject".to_vec()));
+            form.set("Subtype", Object::Name(b"Form".to_vec()));
+            form.set("BBox", bounding_box.clone());
+            form.set("Group", group);
+
+            let form_id = document.add_object(Stream::new(form, Content { operations }.encode()?));
+
+            document.add_xobject(page_id, form_name(index), form_id)?;
+            form_ids.push(form_id);
+        }
+
+        page_forms.push((page_id, form_ids));
+    }
+
+    let graphics_state_ids: BTreeMap<Alpha, ObjectId> = alphas
+        .iter()
+        .map(|&alpha| (alpha, document.add_object(alpha.graphics_state())))
+        .collect();
+
+    for (page_id, form_ids) in page_forms {
+        for (alpha, &graphics_state_id) in graphics_state_ids.iter() {
+            document.add_graphics_state(page_id, alpha.name(), graphics_state_id)?;
+        }
+
+        // The forms use the page's resources, which by now include the forms
+        // nested within them
+        let resources = document.get_dictionary(page_id)?.get(b"Resources")?.clone();
+
+        for form_id in form_ids {
+            document
+                .get_object_mut(form_id)?
+                .as_stream_mut()?
+                .dict
+                .set("Resources", resources.clone());
+        }
+    }
+
+    Ok(())
+}
+
+fn form_name(index: usize) -> String {
+    format!("OpacityForm{}", index)
+}
+
+/// The operations of a page or of one of its groups
+struct Frame {
+    /// In thousandths, unless this is the page
+    opacity: Option<u16>,
+    /// How many marked content sequences other than groups are open
+    marked_depth: usize,
+    operations: Vec<Operation>,
+}
+
+impl Frame {
+    fn new(opacity: Option<u16>) -> Self {
+        Self {
+            opacity,
+            marked_depth: 0,
+            operations: vec![],
+        }
+    }
+}
+
+/// Moves the content of each group into `groups` (innermost first), leaving
+/// the operations that paint it as a form in its place
+fn extract_groups(
+    operations: Vec<Operation>,
+    groups: &mut Vec<(u16, Vec<Operation>)>,
+) -> Vec<Operation> {
+    let mut frames = vec![Frame::new(None)];
+
+    for operation in operations {
+        let group_opacity = match (operation.operator.as_str(), operation.operands.first()) {
+            ("BMC", Some(Object::Name(tag))) => std::str::from_utf8(tag)
+                .ok()
+                .and_then(|tag| tag.strip_prefix(GROUP_TAG))
+                .and_then(|opacity| opacity.parse().ok()),
+            _ => None,
+        };
+
+        let frame = frames.last_mut().expect("The page's frame is never closed");
+
+        match operation.operator.as_str() {
+            _ if group_opacity.is_some() => frames.push(Frame::new(group_opacity)),
+            "EMC" if frame.marked_depth == 0 && frame.opacity.is_some() => {
+                let group = frames.pop().expect("The group's frame is open");
+                let opacity = group.opacity.expect("Only groups have an opacity");
+                let name = form_name(groups.len());
+
+                groups.push((opacity, group.operations));
+
+                let parent = frames.last_mut().expect("The page's frame is never closed");
+                parent.operations.extend([
+                    Operation::new("q", vec![]),
+                    Operation::new(
+                        "gs",
+                        vec![Object::Name(Alpha::Group(opacity).name().into_bytes())],
+                    ),
+                    Operation::new("Do", vec![Object::Name(name.into_bytes())]),
+                    Operation::new("Q", vec![]),
+                ]);
+            }
+            "BMC" | "BDC" => {
+                frame.marked_depth += 1;
+                frame.operations.push(operation);
+            }
+            "EMC" => {
+                frame.marked_depth = frame.marked_depth.saturating_sub(1);
+                frame.operations.push(operation);
+            }
+            _ => frame.operations.push(operation),
+        }
+    }
+
+    // Groups are always closed, but if one wasn't its content stays as it is
+    let mut frames = frames.into_iter();
+    let mut page = frames.next().expect("The page's frame is never closed");
+    page.operations
+        .extend(frames.flat_map(|frame| frame.operations));
+
+    page.operations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operators(operations: &[Operation]) -> Vec<&str> {
+        operations
+            .iter()
+            .map(|operation| operation.operator.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn extracts_nested_groups() {
+        let group = |opacity: u16| {
+            Operation::new(
+                "BMC",
+                vec![Object::Name(
+                    format!("{}{}", GROUP_TAG, opacity).into_bytes(),
+                )],
+            )
+        };
+
+        let operations = vec![
+            Operation::new("BDC", vec![]),
+            Operation::new("re", vec![]),
+            group(500),
+            Operation::new("f", vec![]),
+            group(250),
+            Operation::new("BT", vec![]),
+            Operation::new("ET", vec![]),
+            Operation::new("EMC", vec![]),
+            Operation::new("EMC", vec![]),
+            Operation::new("EMC", vec![]),
+        ];
+
+        let mut groups = vec![];
+        let page = extract_groups(operations, &mut groups);
+
+        assert_eq!(
+            operators(&page),
+            vec!["BDC", "re", "q", "gs", "Do", "Q", "EMC"]
+        );
+        assert_eq!(
+            groups
+                .iter()
+                .map(|(opacity, operations)| (*opacity, operators(operations)))
+                .collect::<Vec<_>>(),
+            vec![
+                (250, vec!["BT", "ET"]),
+                (500, vec!["f", "q", "gs", "Do", "Q"]),
+            ]
+        );
+    }
+}
@@ -1,8 +1,14 @@
 use crate::{
     doc_structure::{TextChild, TextNode},
     error::DocumentGenerationError,
-    rich_text::RichTextSpan,
-    stylesheet::Stylesheet,
+    fonts::FontAttributes,
+    paragraph_layout::ParagraphLayout,
+    rich_text::{
+        hyphenation::hyphenate,
+        span_background::{insert_padding_spacers, SpanBackground},
+        RichTextSpan,
+    },
+    stylesheet::{ResolvedStylesheet, Style},
     utils::node_lookup::NodeLookup,
 };
 
@@ -11,29 +17,65 @@ use super::RichText;
 pub fn dom_node_to_rich_text(
     text_node: &TextNode,
     node_lookup: &NodeLookup,
-    stylesheet: &Stylesheet,
+    stylesheet: &ResolvedStylesheet,
+    paragraph_layout: &ParagraphLayout,
 ) -> Result<RichText, DocumentGenerationError> {
     let text_node_style = node_lookup.get_style(text_node);
+    let span_lengths = node_lookup.get_length_context(text_node);
+    let text_node_lang = node_lookup.get_lang(text_node);
 
     let mut rich_text_spans: Vec<RichTextSpan> = vec![];
+    let mut previous_char = None;
 
     for child in &text_node.children {
         for (node, parent) in child.iter() {
-            let current_style = if let Some(parent) = parent {
+            let (current_style, lang, background) = if let Some(parent) = parent {
                 if let TextChild::TextNode(text_node) = parent {
-                    stylesheet.get_style(text_node_style.clone(), text_node.styles())?
+                    let own_style =
+                        stylesheet.get_mergeable_style(text_node.styles(), span_lengths)?;
+
+                    // Backgrounds are drawn around the nested span itself, so
+                    // they come from its own styles rather than its parent's
+                    let background = SpanBackground::from_style(
+                        text_node.node_id,
+                        &Style::Unmergeable::default().merge_style(&own_style),
+                    );
+
+                    (
+                        text_node_style.merge_style(&own_style),
+                        text_node.lang.as_deref().or(text_node_lang),
+                        background,
+                    )
                 } else {
                     panic!("TextChild::Content cannot have children!");
                 }
             } else {
-                text_node_style.clone()
+                (text_node_style.clone(), text_node_lang, None)
             };
 
             if let TextChild::Content(content) = node {
-                rich_text_spans.push(RichTextSpan::new(content.as_str(), current_style));
+                let text = hyphenate(content, current_style.hyphens, lang);
+                let has_small_caps = paragraph_layout.has_small_caps(
+                    &current_style.font.family,
+                    &FontAttributes {
+                        weight: current_style.font.weight,
+                        style: current_style.font.style,
+                    },
+                );
+
+                rich_text_spans.extend(
+                    RichTextSpan::styled(&text, current_style, previous_char, lang, has_small_caps)
+                        .into_iter()
+                        .map(|span| RichTextSpan {
+                            background: background.clone(),
+                            ..span
+                        }),
+                );
+
+                previous_char = content.chars().last().or(previous_char);
             }
         }
     }
 
-    Ok(RichText(rich_text_spans))
+    Ok(RichText(insert_padding_spacers(rich_text_spans)))
 }
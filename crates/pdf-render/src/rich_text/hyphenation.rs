@@ -0,0 +1,146 @@
+//! Breaks words by inserting soft hyphens, which skia is free to wrap lines
+//!  at, using the hyphenation patterns embedded for each language.
+
+use hypher::Lang;
+
+use crate::stylesheet::Hyphens;
+
+use super::RichText;
+
+pub const SOFT_HYPHEN: char = '\u{AD}';
+
+/// Words shorter than this aren't worth breaking
+const MIN_WORD_LENGTH: usize = 5;
+
+/// The patterns for a BCP 47 language tag (i.e. `en-GB`), if there are any
+fn patterns(lang: &str) -> Option<Lang> {
+    let primary = lang.split(|char| char == '-' || char == '_').next()?;
+
+    match primary.to_ascii_lowercase().as_bytes() {
+        &[first, second] => Lang::from_iso([first, second]),
+        _ => None,
+    }
+}
+
+/// Applies `hyphens` to `text`. Text in a language without hyphenation
+/// patterns is left as it is.
+pub fn hyphenate(text: &str, hyphens: Hyphens, lang: Option<&str>) -> String {
+    match (hyphens, lang.and_then(patterns)) {
+        (Hyphens::None, _) => text.replace(SOFT_HYPHEN, ""),
+        (Hyphens::Auto, Some(patterns)) => insert_soft_hyphens(text, patterns),
+        _ => text.to_owned(),
+    }
+}
+
+fn insert_soft_hyphens(text: &str, patterns: Lang) -> String {
+    let mut hyphenated = String::with_capacity(text.len());
+    let mut word_start = None;
+
+    // A trailing space ends the last word
+    for (index, char) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (word_start, char.is_alphabetic()) {
+            (None, true) => word_start = Some(index),
+            (Some(start), false) => {
+                push_hyphenated_word(&mut hyphenated, &text[start..index], patterns);
+                word_start = None;
+            }
+            _ => {}
+        }
+
+        if word_start.is_none() && index < text.len() {
+            hyphenated.push(char);
+        }
+    }
+
+    hyphenated
+}
+
+fn push_hyphenated_word(text: &mut String, word: &str, patterns: Lang) {
+    if word.chars().count() < MIN_WORD_LENGTH {
+        text.push_str(word);
+        return;
+    }
+
+    for (index, syllable) in hypher::hyphenate(word, patterns).enumerate() {
+        if index > 0 {
+            text.push(SOFT_HYPHEN);
+        }
+
+        text.push_str(syllable);
+    }
+}
+
+/// Removes the soft hyphens from a laid out line. If the line was broken at a
+/// soft hyphen it's replaced by a visible one, and this returns true.
+pub fn resolve_soft_hyphens(line: &mut RichText) -> bool {
+    let is_hyphenated = line
+        .0
+        .last()
+        .map(|span| span.text.ends_with(SOFT_HYPHEN))
+        .unwrap_or(false);
+
+    for span in line.0.iter_mut() {
+        if span.text.contains(SOFT_HYPHEN) {
+            span.text = span.text.replace(SOFT_HYPHEN, "");
+        }
+    }
+
+    if let (true, Some(span)) = (is_hyphenated, line.0.last_mut()) {
+        span.text.push('-');
+    }
+
+    is_hyphenated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visible(text: &str) -> String {
+        text.replace(SOFT_HYPHEN, "|")
+    }
+
+    #[test]
+    fn hyphenates_supported_languages() {
+        assert_eq!(
+            visible(&hyphenate(
+                "Hyphenation, in print.",
+                Hyphens::Auto,
+                Some("en-US")
+            )),
+            "Hy|phen|ation, in print."
+        );
+        assert_eq!(
+            visible(&hyphenate("Silbentrennung", Hyphens::Auto, Some("de_DE"))),
+            "Sil|ben|tren|nung"
+        );
+    }
+
+    #[test]
+    fn leaves_unsupported_text() {
+        assert_eq!(
+            hyphenate("Hyphenation", Hyphens::Auto, Some("x-klingon")),
+            "Hyphenation"
+        );
+        assert_eq!(hyphenate("Hyphenation", Hyphens::Auto, None), "Hyphenation");
+        assert_eq!(
+            hyphenate("Hy\u{AD}phenation", Hyphens::Manual, Some("en")),
+            "Hy\u{AD}phenation"
+        );
+        assert_eq!(
+            hyphenate("Hy\u{AD}phenation", Hyphens::None, Some("en")),
+            "Hyphenation"
+        );
+    }
+
+    #[test]
+    fn shows_the_hyphen_at_the_break() {
+        let mut line = RichText(vec!["A hy\u{AD}phen\u{AD}".into()]);
+        assert!(resolve_soft_hyphens(&mut line));
+        assert_eq!(line.to_string(), "A hyphen-");
+
+        let mut line = RichText(vec!["hy\u{AD}phen ".into()]);
+        assert!(!resolve_soft_hyphens(&mut line));
+        assert_eq!(line.to_string(), "hyphen ");
+    }
+}
@@ -6,11 +6,21 @@ use std::fmt::Display;
 use crate::{
     error::DocumentGenerationError,
     fonts::FontAttributes,
-    stylesheet::{Style, TextTransformation},
+    stylesheet::{Style, TextDecoration, TextTransformation},
     values::{Color, Pt},
 };
 
+use self::{
+    span_background::SpanBackground,
+    typography::{
+        capitalize, smarten_punctuation, synthesize_small_caps, to_lowercase, to_uppercase,
+    },
+};
+
 pub mod dom_node_conversion;
+pub mod hyphenation;
+pub mod span_background;
+mod typography;
 
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct RichTextSpan {
@@ -22,10 +32,51 @@ pub struct RichTextSpan {
     pub color: Color,
     pub letter_spacing: Pt,
     pub line_height: f64,
+    pub text_decoration: TextDecoration::Unmergeable,
+    /// How far the baseline is raised above the line's
+    pub baseline_shift: Pt,
+    /// Only nested spans have their own background
+    pub background: Option<SpanBackground>,
+    /// Whether lower case letters are drawn as the font's own small capitals
+    pub small_caps: bool,
 }
 
 impl RichTextSpan {
-    pub fn new(raw_str: &str, style: Style::Unmergeable) -> Self {
+    /// The spans that draw `raw_str` in `style`. `previous` is the character
+    /// before `raw_str` within the paragraph, and `lang` is the language its
+    /// case is transformed in. Small capitals are synthesized unless
+    /// `has_small_caps` says the font has its own.
+    pub fn styled(
+        raw_str: &str,
+        style: Style::Unmergeable,
+        previous: Option<char>,
+        lang: Option<&str>,
+        has_small_caps: bool,
+    ) -> Vec<Self> {
+        let text = if style.smart_typography {
+            smarten_punctuation(raw_str, previous)
+        } else {
+            raw_str.to_owned()
+        };
+
+        let text = match style.text_transform {
+            TextTransformation::None | TextTransformation::SmallCaps => text,
+            TextTransformation::Uppercase => to_uppercase(&text, lang),
+            TextTransformation::Lowercase => to_lowercase(&text, lang),
+            TextTransformation::Capitalize => capitalize(&text, previous, lang),
+        };
+
+        match style.text_transform {
+            TextTransformation::SmallCaps if has_small_caps => vec![Self {
+                small_caps: true,
+                ..Self::new(text, style)
+            }],
+            TextTransformation::SmallCaps => synthesize_small_caps(Self::new(text, style), lang),
+            _ => vec![Self::new(text, style)],
+        }
+    }
+
+    fn new(text: String, style: Style::Unmergeable) -> Self {
         let line_height = if let Some(line_height) = style.line_height {
             line_height.0 / style.font.size.0
         } else {
@@ -33,20 +84,20 @@ impl RichTextSpan {
         };
 
         Self {
-            text: if style.text_transform == TextTransformation::Uppercase {
-                raw_str.to_uppercase()
-            } else {
-                raw_str.to_owned()
-            },
+            text,
             attributes: FontAttributes {
                 weight: style.font.weight,
                 style: style.font.style,
             },
             color: style.color,
+            baseline_shift: style.vertical_align.baseline_shift(style.font.size),
             font_family: style.font.family,
             size: style.font.size,
             letter_spacing: style.font.letter_spacing,
             line_height,
+            text_decoration: style.text_decoration,
+            background: None,
+            small_caps: false,
         }
     }
 }
@@ -91,6 +142,44 @@ impl CharToByteIndex for String {
 }
 
 impl RichText {
+    pub fn char_count(&self) -> usize {
+        self.0.iter().map(|span| span.text.chars().count()).sum()
+    }
+
+    /// The first `char_count` characters, which unlike `substr` can be none
+    pub fn prefix(&self, char_count: usize) -> Result<RichText, DocumentGenerationError> {
+        if char_count > 0 {
+            self.substr(0, char_count)
+        } else {
+            Ok(RichText(
+                self.0
+                    .iter()
+                    .take(1)
+                    .map(|span| RichTextSpan {
+                        text: String::new(),
+                        ..span.clone()
+                    })
+                    .collect(),
+            ))
+        }
+    }
+
+    /// A copy with every font size (and the spacing that goes with it)
+    /// multiplied by `scale`
+    pub fn scaled(&self, scale: f64) -> RichText {
+        RichText(
+            self.0
+                .iter()
+                .map(|span| RichTextSpan {
+                    size: Pt(span.size.0 * scale),
+                    letter_spacing: Pt(span.letter_spacing.0 * scale),
+                    baseline_shift: Pt(span.baseline_shift.0 * scale),
+                    ..span.clone()
+                })
+                .collect(),
+        )
+    }
+
     pub fn substr(
         &self,
         char_start_index: usize,
@@ -238,6 +327,52 @@ mod tests {
         
     }
 
+    #[test]
+    fn prefix_and_scale() {
+        let line = RichText(vec![
+            RichTextSpan {
+                size: Pt(20.),
+                letter_spacing: Pt(1.),
+                .."Name ".into()
+            },
+            RichTextSpan {
+                size: Pt(10.),
+                .."Surname".into()
+            },
+        ]);
+
+        assert_eq!(line.char_count(), 12);
+        assert_eq!(line.prefix(7).unwrap().to_string(), "Name Su");
+        assert_eq!(line.prefix(0).unwrap().to_string(), "");
+
+        let scaled = line.scaled(0.5);
+        assert_eq!(scaled.0[0].size, Pt(10.));
+        assert_eq!(scaled.0[0].letter_spacing, Pt(0.5));
+        assert_eq!(scaled.0[1].size, Pt(5.));
+    }
+
+    #[test]
+    fn uses_the_fonts_own_small_caps() {
+        let style = Style::Unmergeable {
+            text_transform: TextTransformation::SmallCaps,
+            ..Default::default()
+        };
+
+        let spans = RichTextSpan::styled("Small caps", style.clone(), None, None, true);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].text, "Small caps");
+        assert!(spans[0].small_caps);
+
+        let spans = RichTextSpan::styled("Small caps", style, None, None, false);
+        assert_eq!(
+            spans
+                .iter()
+                .map(|span| (span.text.as_str(), span.small_caps))
+                .collect::<Vec<_>>(),
+            vec![("S", false), ("MALL", false), (" ", false), ("CAPS", false)]
+        );
+    }
+
     #[test]
     #[should_panic]
     fn bad_substr() {
@@ -0,0 +1,139 @@
+//! The backgrounds of nested spans, which are drawn behind each line their
+//!  text is laid out on.
+
+use crate::{
+    doc_structure::NodeId,
+    stylesheet::{BorderRadiusStyle, EdgeStyle, Style},
+    values::{Color, Pt},
+};
+
+use super::RichTextSpan;
+
+/// Reserves the horizontal padding of a background at either end of its
+/// span. The word joiner has no width of its own, so its letter spacing is
+/// all the room it takes, and lines can't be broken next to it.
+pub const SPACER: char = '\u{2060}';
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpanBackground {
+    /// The nested text node whose spans share this background
+    pub node_id: NodeId,
+    pub color: Color,
+    pub padding: EdgeStyle::Unmergeable,
+    pub radius: BorderRadiusStyle::Unmergeable,
+}
+
+impl SpanBackground {
+    /// The background of the text node `node_id`, from the styles set on the
+    /// node itself
+    pub fn from_style(node_id: NodeId, own_style: &Style::Unmergeable) -> Option<Self> {
+        own_style
+            .background_color
+            .clone()
+            .map(|color| SpanBackground {
+                node_id,
+                color,
+                padding: own_style.padding.clone(),
+                radius: own_style.border.radius.clone(),
+            })
+    }
+}
+
+impl RichTextSpan {
+    pub fn is_spacer(&self) -> bool {
+        self.text.chars().eq([SPACER])
+    }
+
+    fn spacer(&self, width: Pt) -> Self {
+        RichTextSpan {
+            text: SPACER.into(),
+            letter_spacing: width,
+            text_decoration: Default::default(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Brackets every run of spans that share a background with spacers. Besides
+/// reserving the padding, they mark where the background starts and ends once
+/// the text is split into lines.
+pub fn insert_padding_spacers(spans: Vec<RichTextSpan>) -> Vec<RichTextSpan> {
+    let mut spaced: Vec<RichTextSpan> = Vec::with_capacity(spans.len());
+    let mut spans = spans.into_iter().peekable();
+
+    while let Some(span) = spans.next() {
+        let background = match &span.background {
+            Some(background) => background.clone(),
+            None => {
+                spaced.push(span);
+                continue;
+            }
+        };
+
+        let starts = spaced
+            .last()
+            .map(|previous| previous.background != span.background)
+            .unwrap_or(true);
+        let ends = spans
+            .peek()
+            .map(|next| next.background != span.background)
+            .unwrap_or(true);
+
+        if starts {
+            spaced.push(span.spacer(background.padding.left));
+        }
+
+        let end_spacer = ends.then(|| span.spacer(background.padding.right));
+
+        spaced.push(span);
+        spaced.extend(end_spacer);
+    }
+
+    spaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brackets_backgrounds_with_spacers() {
+        let background = SpanBackground {
+            node_id: NodeId::new(),
+            color: Color::black(),
+            padding: EdgeStyle::Unmergeable {
+                left: Pt(4.),
+                right: Pt(2.),
+                ..Default::default()
+            },
+            radius: Default::default(),
+        };
+
+        let highlighted = |text: &str| RichTextSpan {
+            background: Some(background.clone()),
+            ..text.into()
+        };
+
+        let spaced = insert_padding_spacers(vec![
+            "Run ".into(),
+            highlighted("cargo"),
+            highlighted(" test"),
+            " now".into(),
+        ]);
+
+        assert_eq!(
+            spaced
+                .iter()
+                .map(|span| (span.is_spacer(), span.letter_spacing))
+                .collect::<Vec<_>>(),
+            vec![
+                (false, Pt(0.)),
+                (true, Pt(4.)),
+                (false, Pt(0.)),
+                (false, Pt(0.)),
+                (true, Pt(2.)),
+                (false, Pt(0.)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Case transformations and typographic substitutions applied to the text of
+//!  a span. Each takes the character before the span within the paragraph,
+//!  since spans can start mid-word, and case transformations take the span's
+//!  language.
+
+use crate::values::Pt;
+
+use super::{hyphenation::SOFT_HYPHEN, RichTextSpan};
+
+/// How much smaller synthesized small capitals are than full capitals
+const SMALL_CAPS_SCALE: f64 = 0.7;
+
+/// Replaces straight quotes with curly quotes, `--` with an en dash, `---`
+/// with an em dash and `...` with an ellipsis.
+pub fn smarten_punctuation(text: &str, previous: Option<char>) -> String {
+    let chars: Vec<char> = text.chars().collect();
+
+    let mut smart = String::with_capacity(text.len());
+    let mut previous = previous;
+    let mut rest = chars.as_slice();
+
+    while let [first, ..] = rest {
+        let (replacement, consumed) = match rest {
+            ['-', '-', '-', ..] => ('—', 3),
+            ['-', '-', ..] => ('–', 2),
+            ['.', '.', '.', ..] => ('…', 3),
+            ['"', ..] if opens_quote(previous) => ('“', 1),
+            ['"', ..] => ('”', 1),
+            ['\'', ..] if opens_quote(previous) => ('‘', 1),
+            ['\'', ..] => ('’', 1),
+            _ => (*first, 1),
+        };
+
+        smart.push(replacement);
+        previous = Some(replacement);
+        rest = &rest[consumed..];
+    }
+
+    smart
+}
+
+/// A quote opens when it starts a word, otherwise it closes (or is an
+/// apostrophe)
+fn opens_quote(previous: Option<char>) -> bool {
+    match previous {
+        None => true,
+        Some(char) => char.is_whitespace() || "([{<‘“—–-".contains(char),
+    }
+}
+
+/// Turkish and Azerbaijani pair a dotted and a dotless i, so `i` upper cases
+/// to `İ` and `I` lower cases to `ı`
+fn has_dotted_i(lang: Option<&str>) -> bool {
+    lang.and_then(|lang| lang.split(|char| char == '-' || char == '_').next())
+        .map_or(false, |primary| {
+            primary.eq_ignore_ascii_case("tr") || primary.eq_ignore_ascii_case("az")
+        })
+}
+
+/// Upper cases `text` following the rules of the BCP 47 language `lang`
+pub fn to_uppercase(text: &str, lang: Option<&str>) -> String {
+    if has_dotted_i(lang) {
+        text.replace('i', "İ").to_uppercase()
+    } else {
+        text.to_uppercase()
+    }
+}
+
+/// Lower cases `text` following the rules of the BCP 47 language `lang`
+pub fn to_lowercase(text: &str, lang: Option<&str>) -> String {
+    if has_dotted_i(lang) {
+        text.replace('I', "ı").replace('İ', "i").to_lowercase()
+    } else {
+        text.to_lowercase()
+    }
+}
+
+/// Upper cases the first letter of every word, leaving the rest of the word
+/// as it is.
+pub fn capitalize(text: &str, previous: Option<char>, lang: Option<&str>) -> String {
+    let mut capitalized = String::with_capacity(text.len());
+    let mut is_in_word = previous.map(is_word_char).unwrap_or(false);
+
+    for char in text.chars() {
+        if !is_in_word && char.is_alphabetic() {
+            push_title_case(&mut capitalized, char, lang);
+        } else {
+            capitalized.push(char);
+        }
+
+        is_in_word = is_word_char(char);
+    }
+
+    capitalized
+}
+
+/// Apostrophes are part of a word so that "don't" doesn't become "Don'T",
+/// and so are the soft hyphens hyphenation adds
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '\'' || char == '’' || char == SOFT_HYPHEN
+}
+
+/// Digraphs have a title case form that differs from their upper case form
+fn push_title_case(text: &mut String, char: char, lang: Option<&str>) {
+    match char {
+        'Ǆ' | 'ǅ' | 'ǆ' => text.push('ǅ'),
+        'Ǉ' | 'ǈ' | 'ǉ' => text.push('ǈ'),
+        'Ǌ' | 'ǋ' | 'ǌ' => text.push('ǋ'),
+        'Ǳ' | 'ǲ' | 'ǳ' => text.push('ǲ'),
+        _ => text.push_str(&to_uppercase(char.encode_utf8(&mut [0; 4]), lang)),
+    }
+}
+
+/// Splits `span` into runs where lower case letters are replaced by smaller
+/// capitals, for fonts without small capitals of their own
+pub fn synthesize_small_caps(span: RichTextSpan, lang: Option<&str>) -> Vec<RichTextSpan> {
+    let mut runs: Vec<(bool, String)> = vec![];
+
+    for char in span.text.chars() {
+        let is_small = char.is_lowercase();
+        let text: String = if is_small {
+            to_uppercase(char.encode_utf8(&mut [0; 4]), lang)
+        } else {
+            char.into()
+        };
+
+        match runs.last_mut() {
+            Some((run_is_small, run_text)) if *run_is_small == is_small => run_text.push_str(&text),
+            _ => runs.push((is_small, text)),
+        }
+    }
+
+    runs.into_iter()
+        .map(|(is_small, text)| {
+            if is_small {
+                RichTextSpan {
+                    text,
+                    size: Pt(span.size.0 * SMALL_CAPS_SCALE),
+                    // The line height is relative to the size, so this keeps
+                    // the line as tall as the rest of the span
+                    line_height: span.line_height / SMALL_CAPS_SCALE,
+                    ..span.clone()
+                }
+            } else {
+                RichTextSpan {
+                    text,
+                    ..span.clone()
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smartens_punctuation() {
+        assert_eq!(
+            smarten_punctuation(
+                r#"She said "it's fine"... twice -- or ---maybe--- not."#,
+                None
+            ),
+            "She said “it’s fine”… twice – or —maybe— not."
+        );
+        assert_eq!(smarten_punctuation("'quoted'", None), "‘quoted’");
+        assert_eq!(smarten_punctuation("\" after", Some('d')), "” after");
+        assert_eq!(smarten_punctuation("(\"a\")", None), "(“a”)");
+    }
+
+    #[test]
+    fn capitalizes_words() {
+        assert_eq!(
+            capitalize("don't stop-me ǆungla éclair", None, None),
+            "Don't Stop-Me ǅungla Éclair"
+        );
+        assert_eq!(capitalize("ing words", Some('w'), None), "ing Words");
+        assert_eq!(capitalize("mIXED case", None, None), "MIXED Case");
+        assert_eq!(capitalize("hy\u{AD}phen", None, None), "Hy\u{AD}phen");
+        assert_eq!(
+            capitalize("istanbul izmir", None, Some("tr")),
+            "İstanbul İzmir"
+        );
+    }
+
+    #[test]
+    fn follows_the_language_case_rules() {
+        assert_eq!(to_uppercase("istanbul", Some("tr-TR")), "İSTANBUL");
+        assert_eq!(to_uppercase("istanbul", Some("en")), "ISTANBUL");
+        assert_eq!(to_uppercase("bakı", Some("az")), "BAKI");
+        assert_eq!(to_lowercase("DİYARBAKIR", Some("tr")), "diyarbakır");
+        assert_eq!(to_lowercase("IRIS", None), "iris");
+    }
+
+    #[test]
+    fn synthesizes_small_caps() {
+        let span = RichTextSpan {
+            size: Pt(10.),
+            .."Hello, NASA!".into()
+        };
+
+        let runs = synthesize_small_caps(span.clone(), None);
+
+        assert_eq!(
+            runs.iter()
+                .map(|run| (run.text.as_str(), run.size))
+                .collect::<Vec<_>>(),
+            vec![("H", Pt(10.)), ("ELLO", Pt(7.)), (", NASA!", Pt(10.)),]
+        );
+
+        let runs = synthesize_small_caps(span, Some("tr"));
+        assert_eq!(runs[1].text, "ELLO");
+        assert_eq!(
+            synthesize_small_caps("izmir".into(), Some("tr"))[0].text,
+            "İZMİR"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use optional_merge_derive::mergeable;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::Deserialize;
+use serde_json::json;
+use ts_rs::TS;
+
+use crate::values::{Pt, Size};
+
+/// How big each tile of a background image is
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(from = "RawBackgroundSize")]
+pub enum BackgroundSize {
+    /// The image's own size, at the document's DPI
+    Auto,
+    /// As small as it can be while covering the whole box
+    Cover,
+    /// As big as it can be while fitting within the box
+    Contain,
+    /// A side that's left out keeps the image's aspect ratio
+    Explicit {
+        width: Option<Pt>,
+        height: Option<Pt>,
+    },
+}
+
+impl Default for BackgroundSize {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+impl BackgroundSize {
+    /// The size of a tile of an image of `natural` size, within a box of
+    /// `area` size
+    pub fn tile_size(&self, natural: Size<Pt>, area: &Size<Pt>) -> Size<Pt> {
+        let scaled = |scale: f64| Size {
+            width: Pt(natural.width.0 * scale),
+            height: Pt(natural.height.0 * scale),
+        };
+
+        let width_scale = area.width.0 / natural.width.0;
+        let height_scale = area.height.0 / natural.height.0;
+
+        match *self {
+            Self::Auto => natural,
+            Self::Cover => scaled(width_scale.max(height_scale)),
+            Self::Contain => scaled(width_scale.min(height_scale)),
+            Self::Explicit {
+                width: Some(width),
+                height: Some(height),
+            } => Size { width, height },
+            Self::Explicit {
+                width: Some(width),
+                height: None,
+            } => scaled(width.0 / natural.width.0),
+            Self::Explicit {
+                width: None,
+                height: Some(height),
+            } => scaled(height.0 / natural.height.0),
+            Self::Explicit {
+                width: None,
+                height: None,
+            } => natural,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+enum BackgroundSizeKeyword {
+    Auto,
+    Cover,
+    Contain,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawBackgroundSize {
+    Keyword(BackgroundSizeKeyword),
+    Explicit {
+        #[serde(default)]
+        width: Option<Pt>,
+        #[serde(default)]
+        height: Option<Pt>,
+    },
+}
+
+impl From<RawBackgroundSize> for BackgroundSize {
+    fn from(raw: RawBackgroundSize) -> Self {
+        match raw {
+            RawBackgroundSize::Keyword(BackgroundSizeKeyword::Auto) => Self::Auto,
+            RawBackgroundSize::Keyword(BackgroundSizeKeyword::Cover) => Self::Cover,
+            RawBackgroundSize::Keyword(BackgroundSizeKeyword::Contain) => Self::Contain,
+            RawBackgroundSize::Explicit { width, height } => Self::Explicit { width, height },
+        }
+    }
+}
+
+impl JsonSchema for BackgroundSize {
+    fn schema_name() -> String {
+        String::from("BackgroundSize")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "description": "A keyword, or the width and height of a tile (a side that's left out keeps the image's aspect ratio)",
+            "anyOf": [
+                { "type": "string", "enum": ["Auto", "Cover", "Contain"] },
+                {
+                    "type": "object",
+                    "properties": {
+                        "width": gen.subschema_for::<Pt>(),
+                        "height": gen.subschema_for::<Pt>()
+                    },
+                    "additionalProperties": false
+                }
+            ]
+        }))
+        .expect("The BackgroundSize schema is a valid JSON schema")
+    }
+}
+
+/// Where the first tile of a background image sits along one side of the
+/// padding box
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(from = "RawBackgroundOffset")]
+pub enum BackgroundOffset {
+    /// At the left or top
+    Start,
+    Center,
+    /// At the right or bottom
+    End,
+    /// This far from the left or top
+    Length(Pt),
+}
+
+impl Default for BackgroundOffset {
+    fn default() -> Self {
+        Self::Start
+    }
+}
+
+impl BackgroundOffset {
+    /// How far a tile of `tile_length` is from the start of a side of
+    /// `area_length`
+    pub fn resolve(&self, tile_length: Pt, area_length: Pt) -> Pt {
+        match *self {
+            Self::Start => Pt(0.),
+            Self::Center => Pt((area_length.0 - tile_length.0) / 2.),
+            Self::End => area_length - tile_length,
+            Self::Length(length) => length,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+enum BackgroundOffsetKeyword {
+    Start,
+    Center,
+    End,
+}
+
+/// The keywords are tried first, so that they aren't parsed as (invalid)
+/// lengths
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawBackgroundOffset {
+    Keyword(BackgroundOffsetKeyword),
+    Length(Pt),
+}
+
+impl From<RawBackgroundOffset> for BackgroundOffset {
+    fn from(raw: RawBackgroundOffset) -> Self {
+        match raw {
+            RawBackgroundOffset::Keyword(BackgroundOffsetKeyword::Start) => Self::Start,
+            RawBackgroundOffset::Keyword(BackgroundOffsetKeyword::Center) => Self::Center,
+            RawBackgroundOffset::Keyword(BackgroundOffsetKeyword::End) => Self::End,
+            RawBackgroundOffset::Length(length) => Self::Length(length),
+        }
+    }
+}
+
+impl JsonSchema for BackgroundOffset {
+    fn schema_name() -> String {
+        String::from("BackgroundOffset")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "description": "Where a background image sits along a side: a keyword, or how far it is from the left or top",
+            "anyOf": [
+                { "type": "string", "enum": ["Start", "Center", "End"] },
+                gen.subschema_for::<Pt>()
+            ]
+        }))
+        .expect("The BackgroundOffset schema is a valid JSON schema")
+    }
+}
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[ts(export, rename_all = "camelCase")]
+pub struct BackgroundPosition {
+    #[ts(type = "\"Start\" | \"Center\" | \"End\" | number | string")]
+    pub x: BackgroundOffset,
+    #[ts(type = "\"Start\" | \"Center\" | \"End\" | number | string")]
+    pub y: BackgroundOffset,
+}
+
+impl Default for BackgroundPosition::Unmergeable {
+    fn default() -> Self {
+        Self {
+            x: BackgroundOffset::Start,
+            y: BackgroundOffset::Start,
+        }
+    }
+}
+
+/// Which ways a background image is tiled from its first tile, to fill the
+/// box
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum BackgroundRepeat {
+    Repeat,
+    RepeatX,
+    RepeatY,
+    NoRepeat,
+}
+
+impl Default for BackgroundRepeat {
+    fn default() -> Self {
+        Self::Repeat
+    }
+}
+
+impl BackgroundRepeat {
+    pub fn repeats_x(&self) -> bool {
+        matches!(self, Self::Repeat | Self::RepeatX)
+    }
+
+    pub fn repeats_y(&self) -> bool {
+        matches!(self, Self::Repeat | Self::RepeatY)
+    }
+}
@@ -0,0 +1,49 @@
+use optional_merge_derive::mergeable;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ts_rs::TS;
+
+/// How the line along an edge of a border is drawn
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum BorderLineStyle {
+    Solid,
+    Dashed,
+    /// Round dots as wide as the border
+    Dotted,
+    /// Two lines, each a third of the border's width
+    Double,
+}
+
+impl Default for BorderLineStyle {
+    fn default() -> Self {
+        Self::Solid
+    }
+}
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
+#[ts(export)]
+pub struct EdgeLineStyle {
+    pub top: BorderLineStyle,
+    pub right: BorderLineStyle,
+    pub bottom: BorderLineStyle,
+    pub left: BorderLineStyle,
+}
+
+impl Default for EdgeLineStyle::Unmergeable {
+    fn default() -> Self {
+        Self::new(BorderLineStyle::Solid)
+    }
+}
+
+impl EdgeLineStyle::Unmergeable {
+    pub fn new(line_style: BorderLineStyle) -> Self {
+        Self {
+            top: line_style,
+            right: line_style,
+            bottom: line_style,
+            left: line_style,
+        }
+    }
+}
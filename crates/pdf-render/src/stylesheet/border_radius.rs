@@ -1,40 +1,106 @@
 use crate::values::Pt;
 use optional_merge_derive::mergeable;
-use ts_rs::TS;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::Deserialize;
+use serde_json::json;
 
 #[mergeable]
-#[derive(TS, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
 #[ts(export, rename_all = "camelCase")]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BorderRadiusStyle {
-    #[ts(type = "string | number")]
-    pub top_right: Pt,
-    #[ts(type = "string | number")]
-    pub bottom_right: Pt,
-    #[ts(type = "string | number")]
-    pub bottom_left: Pt,
-    #[ts(type = "string | number")]
-    pub top_left: Pt,
+    #[ts(type = "string | number | { horizontal: string | number, vertical: string | number }")]
+    pub top_right: CornerRadius,
+    #[ts(type = "string | number | { horizontal: string | number, vertical: string | number }")]
+    pub bottom_right: CornerRadius,
+    #[ts(type = "string | number | { horizontal: string | number, vertical: string | number }")]
+    pub bottom_left: CornerRadius,
+    #[ts(type = "string | number | { horizontal: string | number, vertical: string | number }")]
+    pub top_left: CornerRadius,
 }
 
 impl Default for BorderRadiusStyle::Unmergeable {
     fn default() -> Self {
+        Self::new(Pt(0.))
+    }
+}
+
+impl BorderRadiusStyle::Unmergeable {
+    pub fn new(radius: Pt) -> Self {
         Self {
-            top_right: Pt(0.),
-            bottom_right: Pt(0.),
-            bottom_left: Pt(0.),
-            top_left: Pt(0.),
+            top_right: CornerRadius::new(radius),
+            bottom_right: CornerRadius::new(radius),
+            bottom_left: CornerRadius::new(radius),
+            top_left: CornerRadius::new(radius),
         }
     }
 }
 
-impl BorderRadiusStyle::Unmergeable {
+/// The radii of the quarter ellipse a corner is rounded along, given as one
+/// length for a circle or as `{ "horizontal": ..., "vertical": ... }`
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(from = "CornerRadiusValue")]
+pub struct CornerRadius {
+    pub horizontal: Pt,
+    pub vertical: Pt,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CornerRadiusValue {
+    Circular(Pt),
+    Elliptical { horizontal: Pt, vertical: Pt },
+}
+
+impl From<CornerRadiusValue> for CornerRadius {
+    fn from(value: CornerRadiusValue) -> Self {
+        match value {
+            CornerRadiusValue::Circular(radius) => Self::new(radius),
+            CornerRadiusValue::Elliptical {
+                horizontal,
+                vertical,
+            } => Self {
+                horizontal,
+                vertical,
+            },
+        }
+    }
+}
+
+impl CornerRadius {
     pub fn new(radius: Pt) -> Self {
         Self {
-            top_right: radius,
-            bottom_right: radius,
-            bottom_left: radius,
-            top_left: radius,
+            horizontal: radius,
+            vertical: radius,
         }
     }
+
+    pub fn is_square(&self) -> bool {
+        self.horizontal <= Pt(0.) || self.vertical <= Pt(0.)
+    }
+}
+
+impl JsonSchema for CornerRadius {
+    fn schema_name() -> String {
+        String::from("CornerRadius")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let pt = gen.subschema_for::<Pt>();
+
+        serde_json::from_value(json!({
+            "description": "One radius for a round corner, or a horizontal and a vertical one \
+                for an elliptical corner",
+            "oneOf": [
+                pt,
+                {
+                    "type": "object",
+                    "properties": { "horizontal": pt, "vertical": pt },
+                    "required": ["horizontal", "vertical"],
+                    "additionalProperties": false
+                }
+            ]
+        }))
+        .expect("The CornerRadius schema is a valid JSON schema")
+    }
 }
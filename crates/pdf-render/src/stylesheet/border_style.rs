@@ -1,20 +1,22 @@
 use optional_merge_derive::mergeable;
-use ts_rs::TS;
+use schemars::JsonSchema;
 
 use crate::stylesheet::EdgeStyle;
-use crate::values::Color;
 
-use super::BorderRadiusStyle;
+use super::{BorderRadiusStyle, EdgeColorStyle, EdgeLineStyle};
 
 #[mergeable]
-#[derive(TS, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
 #[ts(export)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BorderStyle {
     #[mergeable(nested)]
     pub width: EdgeStyle,
-    #[ts(type = "string")]
-    pub color: Color,
+    #[mergeable(nested)]
+    #[ts(type = "string | { top?: string, right?: string, bottom?: string, left?: string }")]
+    pub color: EdgeColorStyle,
+    #[mergeable(nested)]
+    pub style: EdgeLineStyle,
     #[mergeable(nested)]
     pub radius: BorderRadiusStyle,
 }
@@ -25,6 +27,7 @@ impl Default for BorderStyle::Unmergeable {
         Self {
             width: Default::default(),
             color: Default::default(),
+            style: Default::default(),
             radius: Default::default(),
         }
     }
@@ -0,0 +1,51 @@
+use optional_merge_derive::mergeable;
+use schemars::JsonSchema;
+
+use crate::values::{Color, Pt};
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[ts(export, rename_all = "camelCase")]
+pub struct BoxShadow {
+    /// How far right the shadow is moved
+    #[ts(type = "string | number")]
+    pub offset_x: Pt,
+    /// How far down the shadow is moved
+    #[ts(type = "string | number")]
+    pub offset_y: Pt,
+    /// How wide the shadow's edge fades over
+    #[ts(type = "string | number")]
+    pub blur: Pt,
+    /// How much bigger than the box the shadow is, or how much smaller if
+    /// it's negative
+    #[ts(type = "string | number")]
+    pub spread: Pt,
+    /// Defaults to the color of the text
+    #[ts(type = "string")]
+    pub color: Option<Color>,
+    /// Casts the shadow inside the border instead of beneath the box
+    pub inset: bool,
+}
+
+impl Default for BoxShadow::Unmergeable {
+    fn default() -> Self {
+        Self {
+            offset_x: Pt(0.),
+            offset_y: Pt(0.),
+            blur: Pt(0.),
+            spread: Pt(0.),
+            color: None,
+            inset: false,
+        }
+    }
+}
+
+impl BoxShadow::Unmergeable {
+    /// Whether any of the shadow would show outside of (or inside) the box
+    pub fn is_visible(&self) -> bool {
+        [self.offset_x, self.offset_y, self.blur, self.spread]
+            .iter()
+            .any(|&length| length != Pt(0.))
+    }
+}
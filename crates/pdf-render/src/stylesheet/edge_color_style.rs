@@ -0,0 +1,101 @@
+use crate::values::Color;
+use merges::Merges;
+use optional_merge_derive::mergeable;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::Deserialize;
+
+/// Given as one color for every edge, or as `{ "top": ..., "left": ... }`
+/// for any of them
+#[mergeable]
+#[derive(Clone, Debug, PartialEq)]
+#[ts(export)]
+#[serde(from = "EdgeColorValue")]
+pub struct EdgeColorStyle {
+    #[ts(type = "string")]
+    pub top: Color,
+    #[ts(type = "string")]
+    pub right: Color,
+    #[ts(type = "string")]
+    pub bottom: Color,
+    #[ts(type = "string")]
+    pub left: Color,
+}
+
+impl Default for EdgeColorStyle::Unmergeable {
+    fn default() -> Self {
+        Self::new(Color::black())
+    }
+}
+
+impl EdgeColorStyle::Unmergeable {
+    pub fn new(color: Color) -> Self {
+        Self {
+            top: color.clone(),
+            right: color.clone(),
+            bottom: color.clone(),
+            left: color,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct EdgeColors {
+    top: Option<Color>,
+    right: Option<Color>,
+    bottom: Option<Color>,
+    left: Option<Color>,
+}
+
+/// A single color is tried first, since an object is never a color
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum EdgeColorValue {
+    All(Color),
+    Edges(EdgeColors),
+}
+
+impl From<EdgeColorValue> for EdgeColorStyle::Mergeable {
+    fn from(value: EdgeColorValue) -> Self {
+        match value {
+            EdgeColorValue::All(color) => EdgeColorStyle::Unmergeable::new(color).into(),
+            EdgeColorValue::Edges(edges) => Self {
+                top: edges.top,
+                right: edges.right,
+                bottom: edges.bottom,
+                left: edges.left,
+            },
+        }
+    }
+}
+
+/// Edges that are left out are the default color
+impl From<EdgeColorValue> for EdgeColorStyle::Unmergeable {
+    fn from(value: EdgeColorValue) -> Self {
+        let defaults = EdgeColorStyle::Mergeable::from(Self::default());
+
+        defaults
+            .merge(&EdgeColorStyle::Mergeable::from(value))
+            .into()
+    }
+}
+
+impl JsonSchema for EdgeColorStyle::Mergeable {
+    fn schema_name() -> String {
+        String::from("EdgeColorStyle")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        EdgeColorValue::json_schema(gen)
+    }
+}
+
+impl JsonSchema for EdgeColorStyle::Unmergeable {
+    fn schema_name() -> String {
+        String::from("ResolvedEdgeColorStyle")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        EdgeColorValue::json_schema(gen)
+    }
+}
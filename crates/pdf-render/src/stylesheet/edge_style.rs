@@ -1,9 +1,9 @@
 use crate::values::Pt;
 use optional_merge_derive::mergeable;
-use ts_rs::TS;
+use schemars::JsonSchema;
 
 #[mergeable]
-#[derive(TS, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
 #[ts(export)]
 pub struct EdgeStyle {
     #[ts(type = "string | number")]
@@ -1,10 +1,10 @@
 use optional_merge_derive::mergeable;
-use ts_rs::TS;
+use schemars::JsonSchema;
 
 use super::{Direction, FlexAlign, FlexWrap};
 
 #[mergeable]
-#[derive(TS, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
 #[ts(export)]
 pub struct FlexStyle {
     // Add other attributes as needed...
@@ -1,14 +1,15 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
-#[derive(TS, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[derive(TS, JsonSchema, Deserialize, Clone, Copy, PartialEq, Debug)]
 #[ts(export)]
 pub enum Direction {
     Column,
     Row,
 }
 
-#[derive(TS, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[derive(TS, JsonSchema, Deserialize, Clone, Copy, PartialEq, Debug)]
 #[ts(export)]
 pub enum FlexWrap {
     NoWrap,
@@ -16,7 +17,7 @@ pub enum FlexWrap {
     WrapReverse,
 }
 
-#[derive(TS, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[derive(TS, JsonSchema, Deserialize, Clone, Copy, PartialEq, Debug)]
 #[ts(export)]
 pub enum FlexAlign {
     Auto,
@@ -1,11 +1,11 @@
 use optional_merge_derive::mergeable;
-use ts_rs::TS;
+use schemars::JsonSchema;
 
 use crate::fonts::{FontSlant, FontWeight};
 use crate::values::Pt;
 
 #[mergeable]
-#[derive(TS, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 #[ts(export, rename_all = "camelCase")]
 pub struct FontStyles {
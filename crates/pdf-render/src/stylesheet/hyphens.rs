@@ -0,0 +1,21 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ts_rs::TS;
+
+/// Where words may be broken across lines
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum Hyphens {
+    /// Never, even at soft hyphens
+    None,
+    /// Only at soft hyphens (U+00AD) within the text
+    Manual,
+    /// Wherever the hyphenation patterns for the text's `lang` allow
+    Auto,
+}
+
+impl Default for Hyphens {
+    fn default() -> Self {
+        Self::Manual
+    }
+}
@@ -0,0 +1,205 @@
+//! Converts lengths that depend on where a style is used (`px`, `em`, `rem`,
+//!  `%`, `vw` and `vh`) within a raw style into points.
+
+use serde_json::Value;
+
+use crate::values::{Length, Pt, Size, DEFAULT_DPI};
+
+use super::FontStyles;
+
+/// Everything relative lengths are measured against, for the node a style is
+/// being computed for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LengthContext {
+    /// Pixels per inch
+    pub dpi: f64,
+    /// What `em` (and `%`) means within `font.size`
+    pub parent_font_size: Pt,
+    /// What `em` (and `%` in `lineHeight` and `verticalAlign`) means
+    /// everywhere else
+    pub font_size: Pt,
+    /// What `rem` means
+    pub root_font_size: Pt,
+    /// What `%` means everywhere else. This is the content width of the
+    /// parent, which is only exact while the parent stretches its children
+    /// across its width (the default for columns).
+    pub containing_block_width: Pt,
+    /// What `vw` and `vh` are percentages of
+    pub page_content_size: Size<Pt>,
+}
+
+impl LengthContext {
+    pub fn new(page_content_size: Size<Pt>, dpi: f64) -> Self {
+        let font_size = FontStyles::Unmergeable::default().size;
+
+        Self {
+            dpi,
+            parent_font_size: font_size,
+            font_size,
+            root_font_size: font_size,
+            containing_block_width: page_content_size.width,
+            page_content_size,
+        }
+    }
+
+    /// The context for the children of a node with the given font size and
+    /// content width
+    pub fn for_children(&self, font_size: Pt, content_width: Pt) -> Self {
+        Self {
+            parent_font_size: font_size,
+            font_size,
+            containing_block_width: content_width,
+            ..self.clone()
+        }
+    }
+
+    /// The context for the root of the document, whose font size is what
+    /// `rem` means for everything below it
+    pub fn for_root_children(&self, font_size: Pt, content_width: Pt) -> Self {
+        Self {
+            root_font_size: font_size,
+            ..self.for_children(font_size, content_width)
+        }
+    }
+
+    fn resolve(&self, length: Length, path: &[&str]) -> Pt {
+        let (em, percent_of) = match path {
+            ["font", "size"] => (self.parent_font_size, self.parent_font_size),
+            ["lineHeight"] | ["verticalAlign"] => (self.font_size, self.font_size),
+            _ => (self.font_size, self.containing_block_width),
+        };
+
+        match length {
+            Length::Pt(pt) => pt,
+            Length::Px(px) => Pt::from_px(px, self.dpi),
+            Length::Em(quantity) => Pt(quantity * em.0),
+            Length::Rem(quantity) => Pt(quantity * self.root_font_size.0),
+            Length::Percent(quantity) => Pt(quantity * percent_of.0 / 100.),
+            Length::Vw(quantity) => Pt(quantity * self.page_content_size.width.0 / 100.),
+            Length::Vh(quantity) => Pt(quantity * self.page_content_size.height.0 / 100.),
+        }
+    }
+}
+
+impl Default for LengthContext {
+    fn default() -> Self {
+        Self::new(Size::from((Pt(0.), Pt(0.))), DEFAULT_DPI)
+    }
+}
+
+/// Returns a copy of `raw_style` where every relative length has been
+/// replaced by the equivalent `pt` string. Strings are kept as strings so that
+/// string fields (i.e. `width`) still parse.
+pub fn resolve_lengths(raw_style: &Value, context: &LengthContext) -> Value {
+    resolve_value(raw_style, context, &mut vec![])
+}
+
+/// Whether `raw_style` has any lengths that `resolve_lengths` would replace,
+/// i.e. whether the style depends on where it's used
+pub fn has_relative_lengths(raw_style: &Value) -> bool {
+    match raw_style {
+        Value::String(str) => !matches!(Length::try_from(str.as_str()), Ok(Length::Pt(_)) | Err(_)),
+        Value::Array(values) => values.iter().any(has_relative_lengths),
+        Value::Object(fields) => fields.values().any(has_relative_lengths),
+        _ => false,
+    }
+}
+
+fn resolve_value<'a>(value: &'a Value, context: &LengthContext, path: &mut Vec<&'a str>) -> Value {
+    match value {
+        Value::String(str) => match Length::try_from(str.as_str()) {
+            Ok(Length::Pt(_)) | Err(_) => value.clone(),
+            Ok(length) => Value::String(format!("{}pt", context.resolve(length, path).0)),
+        },
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| resolve_value(value, context, path))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| {
+                    path.push(key);
+                    let resolved = resolve_value(value, context, path);
+                    path.pop();
+
+                    (key.clone(), resolved)
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn context() -> LengthContext {
+        LengthContext {
+            dpi: 96.,
+            parent_font_size: Pt(10.),
+            font_size: Pt(20.),
+            root_font_size: Pt(16.),
+            containing_block_width: Pt(400.),
+            page_content_size: Size::from((Pt(500.), Pt(700.))),
+        }
+    }
+
+    #[test]
+    fn resolves_relative_lengths() {
+        let resolved = resolve_lengths(
+            &json!({
+                "margin": { "top": "2em", "right": "1rem", "bottom": "10%", "left": "96px" },
+                "padding": { "top": "10vw", "bottom": "10vh" },
+            }),
+            &context(),
+        );
+
+        assert_eq!(
+            resolved,
+            json!({
+                "margin": { "top": "40pt", "right": "16pt", "bottom": "40pt", "left": "72pt" },
+                "padding": { "top": "50pt", "bottom": "70pt" },
+            })
+        );
+    }
+
+    #[test]
+    fn font_size_is_relative_to_the_parent() {
+        let resolved = resolve_lengths(
+            &json!({
+                "font": { "size": "1.5em", "letterSpacing": "0.1em" },
+                "lineHeight": "150%",
+            }),
+            &context(),
+        );
+
+        assert_eq!(
+            resolved,
+            json!({
+                "font": { "size": "15pt", "letterSpacing": "2pt" },
+                "lineHeight": "30pt",
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_everything_else() {
+        let raw_style = json!({
+            "color": "red",
+            "width": "auto",
+            "font": { "family": "Inter", "size": "12pt" },
+            "margin": { "top": 4, "left": "1in" },
+            "flex": { "direction": "Row" },
+        });
+
+        assert_eq!(resolve_lengths(&raw_style, &context()), raw_style);
+        assert!(!has_relative_lengths(&raw_style));
+        assert!(has_relative_lengths(&json!({ "margin": { "top": "1em" } })));
+    }
+}
@@ -4,59 +4,319 @@
 use std::collections::HashMap;
 
 use merges::Merges;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
 use serde::Deserialize;
+use serde_json::Value;
 
+mod background_image;
+mod border_line_style;
 mod border_radius;
 mod border_style;
+mod box_shadow;
+mod edge_color_style;
 mod edge_style;
 mod flex_style;
 mod flex_values;
 mod font_styles;
+mod hyphens;
+mod lengths;
+mod overflow;
 mod page_break_rule;
+mod registry;
 mod style;
+mod text_align;
+mod text_decoration;
+mod text_overflow;
 mod text_transformation;
+mod variables;
+mod vertical_align;
 
-pub use border_radius::BorderRadiusStyle;
+pub use background_image::{
+    BackgroundOffset, BackgroundPosition, BackgroundRepeat, BackgroundSize,
+};
+pub use border_line_style::{BorderLineStyle, EdgeLineStyle};
+pub use border_radius::{BorderRadiusStyle, CornerRadius};
 pub use border_style::BorderStyle;
+pub use box_shadow::BoxShadow;
+pub use edge_color_style::EdgeColorStyle;
 pub use edge_style::EdgeStyle;
 pub use flex_style::FlexStyle;
 pub use flex_values::*;
 pub use font_styles::FontStyles;
+pub use hyphens::Hyphens;
+pub use lengths::LengthContext;
+pub use overflow::Overflow;
 pub use page_break_rule::PageBreakRule;
+pub use registry::StylesheetRegistry;
 pub use style::Style;
+pub use text_align::TextAlign;
+pub use text_decoration::{TextDecoration, TextDecorationLine, TextDecorationStyle};
+pub use text_overflow::{TextFit, TextOverflow};
 pub use text_transformation::TextTransformation;
+pub use variables::StyleVariables;
+pub use vertical_align::VerticalAlign;
 
 use crate::error::{DocumentGenerationError, UserInputError};
 
-#[derive(Deserialize, Debug, Default)]
+use self::{
+    lengths::{has_relative_lengths, resolve_lengths},
+    variables::resolve_variables,
+};
+
+/// The key within a style that lists the styles it inherits from
+const EXTENDS_KEY: &str = "extends";
+
+/// Styles are kept in their raw form so that stylesheets can be merged before
+/// any `var(...)` references are substituted from `variables`, and the result
+/// is then resolved once with `resolve`.
+#[derive(Deserialize, Debug, Default, Clone)]
 pub struct Stylesheet {
+    #[serde(default)]
+    variables: StyleVariables,
     #[serde(flatten)]
-    style_lookup: HashMap<String, Style::Mergeable>,
+    style_lookup: HashMap<String, Value>,
+}
+
+/// Every key other than `variables` is a style, which may also list the styles
+/// it `extends`. `var(...)` references are substituted before a style is
+/// parsed, so they aren't reflected here.
+impl JsonSchema for Stylesheet {
+    fn schema_name() -> String {
+        String::from("Stylesheet")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut style = Style::Mergeable::json_schema(gen).into_object();
+        style.object().properties.insert(
+            String::from(EXTENDS_KEY),
+            gen.subschema_for::<Vec<String>>(),
+        );
+
+        let mut stylesheet = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            ..Default::default()
+        };
+        stylesheet.object().properties.insert(
+            String::from("variables"),
+            gen.subschema_for::<StyleVariables>(),
+        );
+        stylesheet.object().additional_properties = Some(Box::new(style.into()));
+
+        stylesheet.into()
+    }
 }
 
 impl Stylesheet {
+    /// Resolves every style's `var(...)` references and `extends`, and parses
+    /// it, so that a malformed style is reported even if no node uses it.
+    pub fn resolve(&self) -> Result<ResolvedStylesheet, DocumentGenerationError> {
+        let mut class_names: Vec<&String> = self.style_lookup.keys().collect();
+        class_names.sort();
+
+        let mut styles = HashMap::new();
+
+        for class_name in class_names {
+            self.resolve_extended_style(class_name, &mut styles, &mut vec![])?;
+        }
+
+        Ok(ResolvedStylesheet { styles })
+    }
+
+    /// Resolves the styles listed in `extends` (in order) and then layers the
+    /// style's own fields on top of them.
+    fn resolve_extended_style(
+        &self,
+        class_name: &str,
+        styles: &mut HashMap<String, Vec<StyleLayer>>,
+        extends_chain: &mut Vec<String>,
+    ) -> Result<Vec<StyleLayer>, DocumentGenerationError> {
+        if let Some(layers) = styles.get(class_name) {
+            return Ok(layers.clone());
+        }
+
+        if extends_chain.iter().any(|name| name == class_name) {
+            let mut cycle = extends_chain.clone();
+            cycle.push(class_name.to_owned());
+
+            return Err(UserInputError::CyclicStyleExtends {
+                cycle: cycle.join(" -> "),
+            }
+            .into());
+        }
+
+        let raw_style =
+            self.style_lookup
+                .get(class_name)
+                .ok_or_else(|| UserInputError::StyleDoesNotExist {
+                    style_name: class_name.to_owned(),
+                })?;
+
+        let mut resolved_style = resolve_variables(raw_style, &self.variables)?;
+
+        let malformed_style = |source| UserInputError::MalformedStyle {
+            style_name: class_name.to_owned(),
+            source,
+        };
+
+        let extends = match resolved_style.as_object_mut() {
+            Some(fields) => fields.remove(EXTENDS_KEY),
+            None => None,
+        };
+
+        let extends: Vec<String> = match extends {
+            Some(extends) => Vec::deserialize(&extends).map_err(malformed_style)?,
+            None => vec![],
+        };
+
+        extends_chain.push(class_name.to_owned());
+
+        let mut layers = vec![];
+
+        for base_name in extends.iter() {
+            for layer in self.resolve_extended_style(base_name, styles, extends_chain)? {
+                push_layer(&mut layers, layer);
+            }
+        }
+
+        extends_chain.pop();
+
+        let own_layer = if has_relative_lengths(&resolved_style) {
+            // Relative lengths only change the numbers, so any length will do
+            // to check that the style parses
+            Style::Mergeable::deserialize(&resolve_lengths(
+                &resolved_style,
+                &LengthContext::default(),
+            ))
+            .map_err(malformed_style)?;
+
+            StyleLayer::Relative(resolved_style)
+        } else {
+            StyleLayer::Parsed(Box::new(
+                Style::Mergeable::deserialize(&resolved_style).map_err(malformed_style)?,
+            ))
+        };
+
+        push_layer(&mut layers, own_layer);
+
+        styles.insert(class_name.to_owned(), layers.clone());
+
+        Ok(layers)
+    }
+}
+
+/// Part of a resolved style, which is either the style itself or one it
+/// `extends`
+#[derive(Debug, Clone)]
+enum StyleLayer {
+    /// A style whose lengths are all absolute, so it's parsed once
+    Parsed(Box<Style::Mergeable>),
+    /// A style with lengths relative to the node it's used on, which is parsed
+    /// again for each node once they've been converted to points
+    Relative(Value),
+}
+
+/// Adjacent parsed layers are merged ahead of time, so a style without any
+/// relative lengths is a single layer
+fn push_layer(layers: &mut Vec<StyleLayer>, layer: StyleLayer) {
+    if let (Some(StyleLayer::Parsed(last)), StyleLayer::Parsed(style)) = (layers.last_mut(), &layer)
+    {
+        **last = last.merge(style.as_ref());
+    } else {
+        layers.push(layer);
+    }
+}
+
+/// A stylesheet with every style resolved and validated, built with
+/// `Stylesheet::resolve` once the document's stylesheets have been merged.
+#[derive(Debug, Default, Clone)]
+pub struct ResolvedStylesheet {
+    styles: HashMap<String, Vec<StyleLayer>>,
+}
+
+impl ResolvedStylesheet {
+    /// `em` lengths are relative to the font size the styles end up with, so
+    /// if the styles change the font size they're resolved a second time with
+    /// the new one.
     pub fn get_mergeable_style(
         &self,
         class_names: &[String],
+        lengths: &LengthContext,
+    ) -> Result<Style::Mergeable, DocumentGenerationError> {
+        let mergeable = self.merge_class_styles(class_names, lengths)?;
+
+        let font_size = mergeable
+            .font
+            .as_ref()
+            .and_then(|font| font.size)
+            .unwrap_or(lengths.parent_font_size);
+
+        if font_size == lengths.font_size {
+            return Ok(mergeable);
+        }
+
+        self.merge_class_styles(
+            class_names,
+            &LengthContext {
+                font_size,
+                ..lengths.clone()
+            },
+        )
+    }
+
+    fn merge_class_styles(
+        &self,
+        class_names: &[String],
+        lengths: &LengthContext,
     ) -> Result<Style::Mergeable, DocumentGenerationError> {
         class_names
             .iter()
-            .map(|class_name| (class_name, self.style_lookup.get(class_name)))
-            .try_fold(Style::Mergeable::default(), |acc, (class_name, style)| {
-                Ok(
-                    acc.merge(style.ok_or_else(|| UserInputError::StyleDoesNotExist {
-                        style_name: class_name.to_owned(),
-                    })?),
-                )
+            .try_fold(Style::Mergeable::default(), |acc, class_name| {
+                self.merge_style(acc, class_name, lengths)
+            })
+    }
+
+    /// Merges each layer of the style on top of `style`
+    fn merge_style(
+        &self,
+        style: Style::Mergeable,
+        class_name: &str,
+        lengths: &LengthContext,
+    ) -> Result<Style::Mergeable, DocumentGenerationError> {
+        let layers =
+            self.styles
+                .get(class_name)
+                .ok_or_else(|| UserInputError::StyleDoesNotExist {
+                    style_name: class_name.to_owned(),
+                })?;
+
+        layers.iter().try_fold(style, |acc, layer| {
+            Ok(match layer {
+                StyleLayer::Parsed(layer_style) => acc.merge(layer_style.as_ref()),
+                StyleLayer::Relative(raw_style) => {
+                    let layer_style =
+                        Style::Mergeable::deserialize(&resolve_lengths(raw_style, lengths))
+                            .map_err(|source| UserInputError::MalformedStyle {
+                                style_name: class_name.to_owned(),
+                                source,
+                            })?;
+
+                    acc.merge(&layer_style)
+                }
             })
+        })
     }
 
     pub fn get_style(
         &self,
         base_style: Style::Unmergeable,
         class_names: &[String],
+        lengths: &LengthContext,
     ) -> Result<Style::Unmergeable, DocumentGenerationError> {
-        let mergeable = self.get_mergeable_style(class_names)?;
+        let mergeable = self.get_mergeable_style(class_names, lengths)?;
 
         Ok(base_style.merge_style(&mergeable))
     }
@@ -65,68 +325,98 @@ impl Stylesheet {
         &self,
         parent_style: &Style::Mergeable,
         class_names: &[String],
+        lengths: &LengthContext,
     ) -> Result<Style::Mergeable, DocumentGenerationError> {
-        let mergeable = self.get_mergeable_style(class_names)?;
+        let mergeable = self.get_mergeable_style(class_names, lengths)?;
 
-        let inherited_style = mergeable.merge_inherited_styles(parent_style);
+        // With the exception of inherited styles, the target node should win
+        // in all cases. Inherited styles (`#[mergeable(inherit)]` on `Style`)
+        // only fall back to the parent where the target node has no style set.
+        let inherited_style = mergeable.inherit_from(parent_style);
 
         Ok(inherited_style)
     }
 }
 
+/// Merging stylesheets layers the right hand side on top of the left. Styles
+/// and variables defined in both are merged field by field with the right
+/// hand side winning.
+impl Merges for Stylesheet {
+    fn merge(&self, rhs: &Self) -> Self {
+        let mut variables = self.variables.clone();
+        variables.extend(rhs.variables.clone());
+
+        let mut style_lookup = self.style_lookup.clone();
+
+        for (class_name, rhs_style) in rhs.style_lookup.iter() {
+            let merged_style = match style_lookup.get(class_name) {
+                Some(lhs_style) => merge_raw_styles(lhs_style, rhs_style),
+                None => rhs_style.clone(),
+            };
+
+            style_lookup.insert(class_name.clone(), merged_style);
+        }
+
+        Self {
+            variables,
+            style_lookup,
+        }
+    }
+}
+
+fn merge_raw_styles(lhs: &Value, rhs: &Value) -> Value {
+    match (lhs, rhs) {
+        (Value::Object(lhs_fields), Value::Object(rhs_fields)) => {
+            let mut fields = lhs_fields.clone();
+
+            for (key, rhs_value) in rhs_fields.iter() {
+                let merged_value = match fields.get(key) {
+                    Some(lhs_value) => merge_raw_styles(lhs_value, rhs_value),
+                    None => rhs_value.clone(),
+                };
+
+                fields.insert(key.clone(), merged_value);
+            }
+
+            Value::Object(fields)
+        }
+        _ => rhs.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::values::Color;
+    use serde_json::json;
+
+    use crate::values::{Color, Pt, Size};
 
     use super::*;
 
+    fn resolve(stylesheet: Value) -> Result<ResolvedStylesheet, DocumentGenerationError> {
+        serde_json::from_value::<Stylesheet>(stylesheet)
+            .unwrap()
+            .resolve()
+    }
+
     #[test]
     fn style_lookup() {
-        let stylesheet = Stylesheet {
-            style_lookup: [
-                (
-                    "a".to_owned(),
-                    Style::Mergeable {
-                        color: Some(Color::white()),
-                        width: Some("a".to_owned()),
-                        ..Default::default()
-                    },
-                ),
-                (
-                    "b".to_owned(),
-                    Style::Mergeable {
-                        height: Some("b".to_owned()),
-                        ..Default::default()
-                    },
-                ),
-                (
-                    "c".to_owned(),
-                    Style::Mergeable {
-                        width: Some("c".to_owned()),
-                        ..Default::default()
-                    },
-                ),
-                (
-                    "d".to_owned(),
-                    Style::Mergeable {
-                        height: Some("d".to_owned()),
-                        width: Some("d".to_owned()),
-                        color: Some(Color::white()),
-                        ..Default::default()
-                    },
-                ),
-            ]
-            .into_iter()
-            .collect(),
-        };
+        let stylesheet = resolve(json!({
+            "a": { "color": "white", "width": "a" },
+            "b": { "height": "b" },
+            "c": { "width": "c" },
+            "d": { "height": "d", "width": "d", "color": "white" },
+        }))
+        .unwrap();
 
         assert_eq!(
-            stylesheet.get_style(Default::default(), &[]).unwrap(),
+            stylesheet
+                .get_style(Default::default(), &[], &Default::default())
+                .unwrap(),
             Default::default()
         );
         assert_eq!(
             stylesheet
-                .get_style(Default::default(), &["a".to_owned()])
+                .get_style(Default::default(), &["a".to_owned()], &Default::default())
                 .unwrap(),
             Style::Unmergeable {
                 color: Color::white(),
@@ -137,7 +427,11 @@ mod tests {
 
         assert_eq!(
             stylesheet
-                .get_style(Default::default(), &["a".to_owned(), "b".to_owned()])
+                .get_style(
+                    Default::default(),
+                    &["a".to_owned(), "b".to_owned()],
+                    &Default::default()
+                )
                 .unwrap(),
             Style::Unmergeable {
                 color: Color::white(),
@@ -151,7 +445,8 @@ mod tests {
             stylesheet
                 .get_style(
                     Default::default(),
-                    &["a".to_owned(), "b".to_owned(), "c".to_owned()]
+                    &["a".to_owned(), "b".to_owned(), "c".to_owned()],
+                    &Default::default()
                 )
                 .unwrap(),
             Style::Unmergeable {
@@ -171,7 +466,8 @@ mod tests {
                         "b".to_owned(),
                         "c".to_owned(),
                         "d".to_owned()
-                    ]
+                    ],
+                    &Default::default()
                 )
                 .unwrap(),
             Style::Unmergeable {
@@ -184,7 +480,7 @@ mod tests {
 
         assert_eq!(
             stylesheet
-                .get_style(Default::default(), &["b".to_owned()])
+                .get_style(Default::default(), &["b".to_owned()], &Default::default())
                 .unwrap(),
             Style::Unmergeable {
                 height: String::from("b"),
@@ -194,7 +490,11 @@ mod tests {
 
         assert_eq!(
             stylesheet
-                .get_style(Default::default(), &["b".to_owned(), "c".to_owned()])
+                .get_style(
+                    Default::default(),
+                    &["b".to_owned(), "c".to_owned()],
+                    &Default::default()
+                )
                 .unwrap(),
             Style::Unmergeable {
                 height: String::from("b"),
@@ -207,7 +507,8 @@ mod tests {
             stylesheet
                 .get_style(
                     Default::default(),
-                    &["b".to_owned(), "c".to_owned(), "d".to_owned()]
+                    &["b".to_owned(), "c".to_owned(), "d".to_owned()],
+                    &Default::default()
                 )
                 .unwrap(),
             Style::Unmergeable {
@@ -218,4 +519,570 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn resolves_variables() {
+        let stylesheet = resolve(json!({
+            "variables": {
+                "brand-primary": "white",
+                "brand-font": "Inter",
+                "gutter": "12pt"
+            },
+            "card": {
+                "color": "var(brand-primary)",
+                "font": { "family": "var(brand-font)" },
+                "margin": { "left": "var(gutter)" }
+            }
+        }))
+        .unwrap();
+
+        let style = stylesheet
+            .get_style(
+                Default::default(),
+                &["card".to_owned()],
+                &Default::default(),
+            )
+            .unwrap();
+
+        assert_eq!(style.color, Color::white());
+        assert_eq!(style.font.family, "Inter");
+        assert_eq!(style.margin.left, Pt(12.));
+    }
+
+    #[test]
+    fn reports_bad_variables() {
+        assert!(matches!(
+            resolve(json!({ "undefined": { "color": "var(missing)" } })),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::UndefinedStyleVariable { .. }
+            ))
+        ));
+
+        assert!(matches!(
+            resolve(json!({
+                "variables": { "loop": "var(loop)" },
+                "cyclic": { "color": "var(loop)" },
+            })),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::CyclicStyleVariable { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn resolves_extends() {
+        let stylesheet = resolve(json!({
+            "base-card": { "color": "white", "width": "base", "height": "base" },
+            "wide-card": { "extends": ["base-card"], "width": "wide" },
+            "tall-card": { "extends": ["wide-card"], "height": "tall" },
+            "override": { "width": "override" },
+        }))
+        .unwrap();
+
+        assert_eq!(
+            stylesheet
+                .get_style(
+                    Default::default(),
+                    &["tall-card".to_owned()],
+                    &Default::default()
+                )
+                .unwrap(),
+            Style::Unmergeable {
+                color: Color::white(),
+                width: String::from("wide"),
+                height: String::from("tall"),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            stylesheet
+                .get_style(
+                    Default::default(),
+                    &["tall-card".to_owned(), "override".to_owned()],
+                    &Default::default()
+                )
+                .unwrap()
+                .width,
+            "override"
+        );
+    }
+
+    #[test]
+    fn reports_bad_extends() {
+        assert!(matches!(
+            resolve(json!({
+                "a": { "extends": ["b"] },
+                "b": { "extends": ["a"] },
+            })),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::CyclicStyleExtends { cycle }
+            )) if cycle == "a -> b -> a"
+        ));
+
+        assert!(matches!(
+            resolve(json!({ "missing": { "extends": ["does-not-exist"] } })),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::StyleDoesNotExist { .. }
+            ))
+        ));
+
+        assert!(matches!(
+            resolve(json!({ "malformed": { "extends": "a" } })),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::MalformedStyle { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn reports_unused_malformed_styles() {
+        assert!(matches!(
+            resolve(json!({
+                "used": { "color": "white" },
+                "unused": { "color": "not-a-color" },
+                "relative": { "margin": { "top": "1em", "left": "wide" } },
+            })),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::MalformedStyle { style_name, .. }
+            )) if style_name == "relative"
+        ));
+
+        assert!(matches!(
+            resolve(json!({ "unused": { "color": "not-a-color" } })),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::MalformedStyle { style_name, .. }
+            )) if style_name == "unused"
+        ));
+    }
+
+    #[test]
+    fn parses_absolute_styles_once() {
+        let stylesheet = resolve(json!({
+            "base": { "color": "white", "margin": { "top": "4pt" } },
+            "card": { "extends": ["base"], "margin": { "left": 2 } },
+            "heading": { "extends": ["card"], "margin": { "bottom": "1em" } },
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            stylesheet.styles["card"].as_slice(),
+            [StyleLayer::Parsed(_)]
+        ));
+        assert!(matches!(
+            stylesheet.styles["heading"].as_slice(),
+            [StyleLayer::Parsed(_), StyleLayer::Relative(_)]
+        ));
+
+        let heading = stylesheet
+            .get_style(
+                Default::default(),
+                &["heading".to_owned()],
+                &LengthContext::default().for_children(Pt(10.), Pt(400.)),
+            )
+            .unwrap();
+
+        assert_eq!(heading.color, Color::white());
+        assert_eq!(heading.margin.top, Pt(4.));
+        assert_eq!(heading.margin.left, Pt(2.));
+        assert_eq!(heading.margin.bottom, Pt(10.));
+    }
+
+    #[test]
+    fn merges_stylesheets() {
+        let base: Stylesheet = serde_json::from_value(json!({
+            "variables": { "brand": "white", "gutter": "4pt" },
+            "card": { "color": "var(brand)", "margin": { "left": "var(gutter)", "top": "1pt" } },
+            "base-only": { "width": "base" },
+        }))
+        .unwrap();
+
+        let inline: Stylesheet = serde_json::from_value(json!({
+            "variables": { "gutter": "8pt" },
+            "card": { "margin": { "top": "2pt" } },
+        }))
+        .unwrap();
+
+        let stylesheet = base.merge(&inline).resolve().unwrap();
+
+        let card = stylesheet
+            .get_style(
+                Default::default(),
+                &["card".to_owned()],
+                &Default::default(),
+            )
+            .unwrap();
+
+        assert_eq!(card.color, Color::white());
+        assert_eq!(card.margin.left, Pt(8.));
+        assert_eq!(card.margin.top, Pt(2.));
+
+        assert_eq!(
+            stylesheet
+                .get_style(
+                    Default::default(),
+                    &["base-only".to_owned()],
+                    &Default::default()
+                )
+                .unwrap()
+                .width,
+            "base"
+        );
+    }
+
+    #[test]
+    fn inherits_styles() {
+        let stylesheet = resolve(json!({
+            "parent": {
+                "color": "white",
+                "lineHeight": "18pt",
+                "textTransform": "Uppercase",
+                "font": { "family": "Inter", "size": "14pt" },
+                "width": "parent",
+                "margin": { "top": "4pt" },
+                "textDecoration": { "line": "Underline", "color": "black" },
+            },
+            "child": { "font": { "size": "10pt" }, "textDecoration": { "style": "Double" } },
+        }))
+        .unwrap();
+
+        let parent_style = stylesheet
+            .compute_mergeable_style(
+                &Default::default(),
+                &["parent".to_owned()],
+                &Default::default(),
+            )
+            .unwrap();
+
+        let child_style = Style::Unmergeable::default().merge_style(
+            &stylesheet
+                .compute_mergeable_style(&parent_style, &["child".to_owned()], &Default::default())
+                .unwrap(),
+        );
+
+        assert_eq!(child_style.color, Color::white());
+        assert_eq!(child_style.line_height, Some(Pt(18.)));
+        assert_eq!(child_style.text_transform, TextTransformation::Uppercase);
+        assert_eq!(child_style.font.family, "Inter");
+        assert_eq!(child_style.font.size, Pt(10.));
+        assert_eq!(
+            child_style.text_decoration,
+            TextDecoration::Unmergeable {
+                line: TextDecorationLine::Underline,
+                color: Some(Color::black()),
+                style: TextDecorationStyle::Double,
+                thickness: None,
+            }
+        );
+
+        assert_eq!(child_style.width, Style::Unmergeable::default().width);
+        assert_eq!(child_style.margin.top, Pt(0.));
+    }
+
+    #[test]
+    fn resolves_em_against_own_font_size() {
+        let stylesheet = resolve(json!({
+            "heading": { "font": { "size": "2em" }, "margin": { "top": "1em", "left": "50%" } },
+            "body": { "margin": { "top": "1em" } },
+        }))
+        .unwrap();
+
+        let lengths = LengthContext::default().for_children(Pt(10.), Pt(400.));
+
+        let heading = stylesheet
+            .get_style(Default::default(), &["heading".to_owned()], &lengths)
+            .unwrap();
+
+        assert_eq!(heading.font.size, Pt(20.));
+        assert_eq!(heading.margin.top, Pt(20.));
+        assert_eq!(heading.margin.left, Pt(200.));
+
+        let body = stylesheet
+            .get_style(Default::default(), &["body".to_owned()], &lengths)
+            .unwrap();
+
+        assert_eq!(body.margin.top, Pt(10.));
+    }
+
+    #[test]
+    fn parses_vertical_align() {
+        let stylesheet = resolve(json!({
+            "footnote": { "verticalAlign": "Super" },
+            "raised": { "verticalAlign": "0.5em" },
+            "lowered": { "verticalAlign": -2 },
+            "dropped": { "verticalAlign": "-2pt" },
+            "inferior": { "verticalAlign": "-0.3em" },
+        }))
+        .unwrap();
+
+        let lengths = LengthContext::default().for_children(Pt(10.), Pt(400.));
+        let vertical_align = |class_name: &str| {
+            stylesheet
+                .get_style(Default::default(), &[class_name.to_owned()], &lengths)
+                .unwrap()
+                .vertical_align
+        };
+
+        assert_eq!(vertical_align("footnote"), VerticalAlign::Super);
+        assert_eq!(vertical_align("raised"), VerticalAlign::Length(Pt(5.)));
+        assert_eq!(vertical_align("lowered"), VerticalAlign::Length(Pt(-2.)));
+        assert_eq!(vertical_align("dropped"), VerticalAlign::Length(Pt(-2.)));
+        assert_eq!(vertical_align("inferior"), VerticalAlign::Length(Pt(-3.)));
+        assert_eq!(VerticalAlign::Sub.baseline_shift(Pt(10.)), Pt(-2.));
+    }
+
+    #[test]
+    fn parses_per_edge_borders() {
+        let stylesheet = resolve(json!({
+            "card": {
+                "border": {
+                    "width": { "bottom": "2pt" },
+                    "color": { "bottom": "white" },
+                    "style": { "bottom": "Dashed", "left": "Double" },
+                },
+            },
+        }))
+        .unwrap();
+
+        let border = stylesheet
+            .get_style(
+                Default::default(),
+                &["card".to_owned()],
+                &LengthContext::default(),
+            )
+            .unwrap()
+            .border;
+
+        assert_eq!(border.width.bottom, Pt(2.));
+        assert_eq!(border.color.bottom, Color::white());
+        assert_eq!(border.color.top, Color::black());
+        assert_eq!(border.style.bottom, BorderLineStyle::Dashed);
+        assert_eq!(border.style.left, BorderLineStyle::Double);
+        assert_eq!(border.style.top, BorderLineStyle::Solid);
+    }
+
+    #[test]
+    fn parses_one_border_color_for_every_edge() {
+        let stylesheet = resolve(json!({
+            "card": { "border": { "color": "#0080AD" } },
+            "highlighted": { "border": { "color": { "left": "white" } } },
+        }))
+        .unwrap();
+
+        let border = |class_names: &[&str]| {
+            stylesheet
+                .get_style(
+                    Default::default(),
+                    &class_names
+                        .iter()
+                        .map(|&class_name| class_name.to_owned())
+                        .collect::<Vec<_>>(),
+                    &LengthContext::default(),
+                )
+                .unwrap()
+                .border
+        };
+
+        let blue = Color::try_from("#0080AD").unwrap();
+
+        assert_eq!(
+            border(&["card"]).color,
+            EdgeColorStyle::Unmergeable::new(blue.clone())
+        );
+
+        let highlighted = border(&["card", "highlighted"]);
+        assert_eq!(highlighted.color.left, Color::white());
+        assert_eq!(highlighted.color.top, blue);
+    }
+
+    #[test]
+    fn parses_elliptical_radii() {
+        let stylesheet = resolve(json!({
+            "pill": {
+                "border": {
+                    "radius": {
+                        "topLeft": "4pt",
+                        "topRight": { "horizontal": "12pt", "vertical": 6 },
+                    },
+                },
+            },
+        }))
+        .unwrap();
+
+        let radius = stylesheet
+            .get_style(
+                Default::default(),
+                &["pill".to_owned()],
+                &LengthContext::default(),
+            )
+            .unwrap()
+            .border
+            .radius;
+
+        assert_eq!(radius.top_left, CornerRadius::new(Pt(4.)));
+        assert_eq!(
+            radius.top_right,
+            CornerRadius {
+                horizontal: Pt(12.),
+                vertical: Pt(6.)
+            }
+        );
+        assert_eq!(radius.bottom_left, CornerRadius::new(Pt(0.)));
+    }
+
+    #[test]
+    fn parses_box_shadows() {
+        let stylesheet = resolve(json!({
+            "card": { "boxShadow": { "offsetY": "0.5em", "blur": 8, "color": "black" } },
+            "pressed": { "boxShadow": { "inset": true } },
+            "lifted": { "boxShadow": { "offsetX": "-2pt", "offsetY": "-0.5em" } },
+        }))
+        .unwrap();
+
+        let lengths = LengthContext::default().for_children(Pt(10.), Pt(400.));
+        let box_shadow = |class_names: &[&str]| {
+            stylesheet
+                .get_style(
+                    Default::default(),
+                    &class_names
+                        .iter()
+                        .map(|&class_name| class_name.to_owned())
+                        .collect::<Vec<_>>(),
+                    &lengths,
+                )
+                .unwrap()
+                .box_shadow
+        };
+
+        let card = box_shadow(&["card"]);
+        assert_eq!(card.offset_y, Pt(5.));
+        assert_eq!(card.blur, Pt(8.));
+        assert_eq!(card.color, Some(Color::black()));
+        assert!(card.is_visible() && !card.inset);
+
+        let pressed = box_shadow(&["card", "pressed"]);
+        assert_eq!(pressed.blur, Pt(8.));
+        assert!(pressed.inset);
+
+        assert!(!box_shadow(&["pressed"]).is_visible());
+
+        let lifted = box_shadow(&["lifted"]);
+        assert_eq!(lifted.offset_x, Pt(-2.));
+        assert_eq!(lifted.offset_y, Pt(-5.));
+    }
+
+    #[test]
+    fn parses_background_images() {
+        let stylesheet = resolve(json!({
+            "cover": {
+                "backgroundImage": "assets/cover.png",
+                "backgroundSize": "Cover",
+                "backgroundPosition": { "x": "Center", "y": "2em" },
+                "backgroundRepeat": "NoRepeat",
+            },
+            "tiled": { "backgroundSize": { "width": "1em" } },
+        }))
+        .unwrap();
+
+        let lengths = LengthContext::default().for_children(Pt(10.), Pt(400.));
+        let style = |class_names: &[&str]| {
+            stylesheet
+                .get_style(
+                    Default::default(),
+                    &class_names
+                        .iter()
+                        .map(|&class_name| class_name.to_owned())
+                        .collect::<Vec<_>>(),
+                    &lengths,
+                )
+                .unwrap()
+        };
+
+        let cover = style(&["cover"]);
+        assert_eq!(cover.background_image.as_deref(), Some("assets/cover.png"));
+        assert_eq!(cover.background_size, BackgroundSize::Cover);
+        assert_eq!(cover.background_position.x, BackgroundOffset::Center);
+        assert_eq!(
+            cover.background_position.y,
+            BackgroundOffset::Length(Pt(20.))
+        );
+        assert_eq!(cover.background_repeat, BackgroundRepeat::NoRepeat);
+
+        let natural = Size::from((Pt(40.), Pt(20.)));
+        assert_eq!(
+            cover
+                .background_size
+                .tile_size(natural.clone(), &Size::from((Pt(100.), Pt(100.)))),
+            Size::from((Pt(200.), Pt(100.)))
+        );
+
+        let tiled = style(&["cover", "tiled"]);
+        assert_eq!(tiled.background_position.x, BackgroundOffset::Center);
+        assert_eq!(
+            tiled
+                .background_size
+                .tile_size(natural, &Size::from((Pt(100.), Pt(100.)))),
+            Size::from((Pt(10.), Pt(5.)))
+        );
+    }
+
+    #[test]
+    fn does_not_inherit_overflow() {
+        let stylesheet = resolve(json!({
+            "card": { "overflow": "Hidden" },
+        }))
+        .unwrap();
+
+        let parent_style = stylesheet
+            .compute_mergeable_style(
+                &Default::default(),
+                &["card".to_owned()],
+                &Default::default(),
+            )
+            .unwrap();
+        assert_eq!(parent_style.overflow, Some(Overflow::Hidden));
+
+        let child_style = Style::Unmergeable::default().merge_style(
+            &stylesheet
+                .compute_mergeable_style(&parent_style, &[], &Default::default())
+                .unwrap(),
+        );
+        assert_eq!(child_style.overflow, Overflow::Visible);
+    }
+
+    #[test]
+    fn stylesheet_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(Stylesheet)).unwrap();
+
+        assert_eq!(schema["properties"]["variables"]["type"], json!("object"));
+
+        let style = &schema["additionalProperties"];
+
+        assert_eq!(style["additionalProperties"], json!(false));
+        assert_eq!(
+            style["properties"]["extends"]["items"],
+            json!({ "type": "string" })
+        );
+        assert_eq!(
+            style["properties"]["color"]["anyOf"][0],
+            json!({ "$ref": "#/definitions/Color" })
+        );
+        assert!(schema["definitions"]["Pt"]["oneOf"].is_array());
+    }
+
+    #[test]
+    fn length_schema_allows_negative_lengths() {
+        let schema = serde_json::to_value(schemars::schema_for!(Stylesheet)).unwrap();
+        let pattern = schema["definitions"]["Pt"]["oneOf"][1]["pattern"]
+            .as_str()
+            .unwrap();
+        let pattern = regex::Regex::new(pattern).unwrap();
+
+        for length in ["2pt", "-2pt", "-0.3em", ".5EM", "12"] {
+            assert!(pattern.is_match(length), "{} should match", length);
+        }
+        for length in ["2 pt", "--2pt", "2pt-"] {
+            assert!(!pattern.is_match(length), "{} shouldn't match", length);
+        }
+    }
 }
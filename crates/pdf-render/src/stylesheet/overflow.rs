@@ -0,0 +1,19 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ts_rs::TS;
+
+/// What happens to the parts of a node's children that reach outside of it
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum Overflow {
+    Visible,
+    /// Clips the children to the padding box, following the rounded corners
+    /// of the border
+    Hidden,
+}
+
+impl Default for Overflow {
+    fn default() -> Self {
+        Self::Visible
+    }
+}
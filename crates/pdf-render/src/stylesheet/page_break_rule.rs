@@ -1,7 +1,8 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
-#[derive(TS, Clone, Debug, PartialEq, Deserialize)]
+#[derive(TS, JsonSchema, Clone, Debug, PartialEq, Deserialize)]
 #[ts(export)]
 pub enum PageBreakRule {
     Auto,
@@ -0,0 +1,263 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use merges::Merges;
+
+use crate::{
+    doc_structure::StylesheetImport,
+    error::{DocumentGenerationError, InternalServerError, UserInputError},
+    utils::contained_path::resolve_contained_path,
+};
+
+use super::{ResolvedStylesheet, Stylesheet};
+
+/// Shared stylesheets that documents can import by name instead of inlining
+/// them in every request.
+#[derive(Default)]
+pub struct StylesheetRegistry {
+    stylesheets: HashMap<String, Stylesheet>,
+    /// Where `File` imports are read from, which are rejected without one
+    import_directory: Option<PathBuf>,
+}
+
+impl StylesheetRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, name: &str, stylesheet: Stylesheet) -> &mut Self {
+        self.stylesheets.insert(name.to_owned(), stylesheet);
+
+        self
+    }
+
+    /// Registers every `*.json` file in `directory` under its file name
+    /// without the extension.
+    pub fn load_directory(
+        &mut self,
+        directory: impl AsRef<Path>,
+    ) -> Result<&mut Self, DocumentGenerationError> {
+        let load_error = |path: &Path, source: Box<dyn std::error::Error>| {
+            InternalServerError::LoadStylesheetError {
+                source,
+                path: path.display().to_string(),
+            }
+        };
+
+        let directory = directory.as_ref();
+        let entries =
+            std::fs::read_dir(directory).map_err(|e| load_error(directory, Box::new(e)))?;
+
+        for entry in entries {
+            let path = entry
+                .map_err(|e| load_error(directory, Box::new(e)))?
+                .path();
+
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "json")
+            {
+                continue;
+            }
+
+            let stylesheet = read_stylesheet(&path).map_err(|e| load_error(&path, e))?;
+
+            let name = path
+                .file_stem()
+                .expect("Paths with an extension also have a file stem")
+                .to_string_lossy();
+
+            self.register(&name, stylesheet);
+        }
+
+        Ok(self)
+    }
+
+    /// Lets documents import stylesheet files from within `directory`, which
+    /// their paths are relative to. Documents that come from untrusted
+    /// clients should only import registered stylesheets.
+    pub fn allow_file_imports(&mut self, directory: impl AsRef<Path>) -> &mut Self {
+        self.import_directory = Some(directory.as_ref().to_owned());
+
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Stylesheet> {
+        self.stylesheets.get(name)
+    }
+
+    /// Builds the stylesheet a document should be rendered with by merging
+    /// each import, in order, and then the document's inline stylesheet, and
+    /// resolving the result.
+    pub fn compose(
+        &self,
+        imports: &[StylesheetImport],
+        inline_stylesheet: &Stylesheet,
+    ) -> Result<ResolvedStylesheet, DocumentGenerationError> {
+        let mut stylesheet = Stylesheet::default();
+
+        for import in imports {
+            let imported = match import {
+                StylesheetImport::File { path } => self.read_import(path)?,
+                StylesheetImport::Registered { name } => self
+                    .get(name)
+                    .ok_or_else(|| UserInputError::StylesheetNotRegistered { name: name.clone() })?
+                    .clone(),
+            };
+
+            stylesheet = stylesheet.merge(&imported);
+        }
+
+        stylesheet.merge(inline_stylesheet).resolve()
+    }
+
+    /// Errors only say where an imported file is malformed, rather than
+    /// echoing any of its contents back
+    fn read_import(&self, path: &str) -> Result<Stylesheet, UserInputError> {
+        let directory = self.import_directory.as_ref().ok_or_else(|| {
+            UserInputError::StylesheetFileImportsDisabled {
+                path: path.to_owned(),
+            }
+        })?;
+
+        let full_path = resolve_contained_path(directory, path).ok_or_else(|| {
+            UserInputError::StylesheetFileNotFound {
+                path: path.to_owned(),
+            }
+        })?;
+
+        let import_error =
+            |source: Box<dyn std::error::Error>| UserInputError::StylesheetImportError {
+                source,
+                path: path.to_owned(),
+            };
+
+        let contents = std::fs::read_to_string(full_path).map_err(|e| import_error(Box::new(e)))?;
+
+        serde_json::from_str(&contents).map_err(|e| {
+            import_error(
+                format!(
+                    "not a valid stylesheet at line {}, column {}",
+                    e.line(),
+                    e.column()
+                )
+                .into(),
+            )
+        })
+    }
+}
+
+fn read_stylesheet(path: impl AsRef<Path>) -> Result<Stylesheet, Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path)?;
+
+    Ok(serde_json::from_str(&contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn composes_registered_stylesheets() {
+        let mut registry = StylesheetRegistry::new();
+
+        registry
+            .register(
+                "base",
+                serde_json::from_value(json!({
+                    "card": { "width": "base", "height": "base" },
+                }))
+                .unwrap(),
+            )
+            .register(
+                "theme",
+                serde_json::from_value(json!({
+                    "card": { "height": "theme" },
+                }))
+                .unwrap(),
+            );
+
+        let inline: Stylesheet = serde_json::from_value(json!({
+            "card": { "width": "inline" },
+        }))
+        .unwrap();
+
+        let stylesheet = registry
+            .compose(
+                &[
+                    StylesheetImport::Registered {
+                        name: "base".to_owned(),
+                    },
+                    StylesheetImport::Registered {
+                        name: "theme".to_owned(),
+                    },
+                ],
+                &inline,
+            )
+            .unwrap();
+
+        let card = stylesheet
+            .get_style(
+                Default::default(),
+                &["card".to_owned()],
+                &Default::default(),
+            )
+            .unwrap();
+
+        assert_eq!(card.width, "inline");
+        assert_eq!(card.height, "theme");
+    }
+
+    #[test]
+    fn missing_imports() {
+        let registry = StylesheetRegistry::new();
+
+        assert!(matches!(
+            registry.compose(
+                &[StylesheetImport::Registered {
+                    name: "missing".to_owned()
+                }],
+                &Stylesheet::default()
+            ),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::StylesheetNotRegistered { .. }
+            ))
+        ));
+
+        let import = |path: &str| StylesheetImport::File {
+            path: path.to_owned(),
+        };
+
+        assert!(matches!(
+            registry.compose(&[import("./does-not-exist.json")], &Stylesheet::default()),
+            Err(DocumentGenerationError::UserInputError(
+                UserInputError::StylesheetFileImportsDisabled { .. }
+            ))
+        ));
+
+        let mut registry = StylesheetRegistry::new();
+        registry.allow_file_imports(env!("CARGO_MANIFEST_DIR"));
+
+        for path in ["./does-not-exist.json", "../../Cargo.toml", "/etc/hosts"] {
+            assert!(matches!(
+                registry.compose(&[import(path)], &Stylesheet::default()),
+                Err(DocumentGenerationError::UserInputError(
+                    UserInputError::StylesheetFileNotFound { .. }
+                ))
+            ));
+        }
+
+        let malformed = registry.compose(&[import("Cargo.toml")], &Stylesheet::default());
+
+        match malformed {
+            Err(DocumentGenerationError::UserInputError(
+                error @ UserInputError::StylesheetImportError { .. },
+            )) => assert!(!error.to_string().contains("package")),
+            _ => panic!("Cargo.toml should fail to import"),
+        }
+    }
+}
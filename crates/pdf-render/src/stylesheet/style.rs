@@ -1,21 +1,28 @@
 use merges::Merges;
 use optional_merge_derive::mergeable;
+use schemars::JsonSchema;
 
-use ts_rs::TS;
+use crate::{
+    error::{DocumentGenerationError, UserInputError},
+    values::{Color, Gradient, Length, Pt},
+};
 
-use crate::values::{Color, Pt};
-
-use super::{BorderStyle, EdgeStyle, FlexStyle, FontStyles, PageBreakRule, TextTransformation};
+use super::{
+    BackgroundPosition, BackgroundRepeat, BackgroundSize, BorderStyle, BoxShadow, EdgeStyle,
+    FlexStyle, FontStyles, Hyphens, Overflow, PageBreakRule, TextAlign, TextDecoration, TextFit,
+    TextOverflow, TextTransformation, VerticalAlign,
+};
 
 #[mergeable]
-#[derive(TS, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
 #[ts(export, rename_all = "camelCase")]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Style {
     #[mergeable(nested)]
     pub border: BorderStyle,
-    #[mergeable(nested)]
+    #[mergeable(nested, inherit)]
     pub font: FontStyles,
+    #[mergeable(inherit)]
     #[ts(type = "string")]
     pub color: Color,
     #[mergeable(nested)]
@@ -24,17 +31,68 @@ pub struct Style {
     pub padding: EdgeStyle,
     #[ts(type = "string")]
     pub background_color: Option<Color>,
+    /// A linear or radial gradient, painted over the background color
+    #[ts(type = "string")]
+    pub background: Option<Gradient>,
+    /// The path of a PNG or JPEG image, painted over the background color and
+    /// gradient and clipped to the corners of the border
+    #[ts(type = "string")]
+    pub background_image: Option<String>,
+    #[ts(
+        type = "\"Auto\" | \"Cover\" | \"Contain\" | { width?: string | number, height?: string | number }"
+    )]
+    pub background_size: BackgroundSize,
+    /// Where the first tile is within the padding box
+    #[mergeable(nested)]
+    pub background_position: BackgroundPosition,
+    pub background_repeat: BackgroundRepeat,
+    /// Fades the node and everything within it as a whole, from 0
+    /// (transparent) to 1 (opaque)
+    pub opacity: f64,
+    /// Beneath the background, following the corners of the border
+    #[mergeable(nested)]
+    pub box_shadow: BoxShadow,
+    /// Clips the node's descendants to its padding box, on every page it
+    /// spans
+    pub overflow: Overflow,
     #[mergeable(nested)]
     pub flex: FlexStyle,
     pub width: String,
     pub height: String,
+    #[mergeable(inherit)]
     pub debug: bool,
     pub break_before: PageBreakRule,
     pub break_after: PageBreakRule,
     pub break_inside: PageBreakRule,
+    /// The fewest lines of a paragraph left at the bottom of a page
+    #[mergeable(inherit)]
+    pub orphans: usize,
+    /// The fewest lines of a paragraph carried to the top of a page
+    #[mergeable(inherit)]
+    pub widows: usize,
+    #[mergeable(inherit)]
     pub text_transform: TextTransformation,
+    /// Replaces straight quotes, `--`, `---` and `...` with their typographic
+    /// equivalents
+    #[mergeable(inherit)]
+    pub smart_typography: bool,
+    #[mergeable(inherit)]
+    pub hyphens: Hyphens,
+    #[mergeable(inherit)]
+    pub text_align: TextAlign,
+    #[mergeable(nested, inherit)]
+    pub text_decoration: TextDecoration,
+    #[ts(type = "\"Baseline\" | \"Super\" | \"Sub\" | number | string")]
+    pub vertical_align: VerticalAlign,
+    #[mergeable(inherit)]
     #[ts(type = "number | string")]
     pub line_height: Option<Pt>,
+    #[ts(type = "number")]
+    pub max_lines: Option<usize>,
+    pub text_overflow: TextOverflow,
+    pub fit: TextFit,
+    #[ts(type = "number | string")]
+    pub min_font_size: Pt,
 }
 
 impl Default for Style::Unmergeable {
@@ -42,6 +100,14 @@ impl Default for Style::Unmergeable {
         Self {
             color: Color::black(),
             background_color: None,
+            background: None,
+            background_image: None,
+            background_size: Default::default(),
+            background_position: Default::default(),
+            background_repeat: Default::default(),
+            opacity: 1.,
+            box_shadow: Default::default(),
+            overflow: Default::default(),
             width: String::from("auto"),
             height: String::from("auto"),
             border: Default::default(),
@@ -50,50 +116,58 @@ impl Default for Style::Unmergeable {
             padding: Default::default(),
             flex: Default::default(),
             text_transform: Default::default(),
+            smart_typography: false,
+            hyphens: Default::default(),
+            text_align: Default::default(),
+            text_decoration: Default::default(),
+            vertical_align: Default::default(),
             break_before: Default::default(),
             break_after: Default::default(),
             break_inside: Default::default(),
+            orphans: 2,
+            widows: 2,
             line_height: None,
+            max_lines: None,
+            text_overflow: Default::default(),
+            fit: Default::default(),
+            min_font_size: Pt(6.),
             debug: false,
         }
     }
 }
 
-impl Style::Mergeable {
-    /// This is meant to emulate how if you set a color on a parent, the child
-    /// gets that color by default unless overridden
-    ///
-    /// With the exception of inherited styles, the target node, self, should win in all cases,
-    /// even if the parent has a style where the target node does not.
-    /// For inherited styles, inherited styles should only "win" where the
-    /// target node does not have any style set.
-    pub fn merge_inherited_styles(&self, parent_style: &Style::Mergeable) -> Style::Mergeable {
-        let mut style = self.clone();
+impl Style::Unmergeable {
+    pub fn merge_style(&self, rhs: &Style::Mergeable) -> Style::Unmergeable {
+        let base = Style::Mergeable::from(self.clone());
 
-        style.font = if let Some(font) = &parent_style.font {
-            font.merge_optional(&style.font)
-        } else {
-            style.font.clone()
-        };
+        let merged: Style::Mergeable = base.merge(rhs);
 
-        if style.color.is_none() {
-            style.color = parent_style.color.clone();
-        }
+        merged.into()
+    }
 
-        if style.debug.is_none() {
-            style.debug = parent_style.debug;
-        }
+    /// The width of the node's border box, unless it's `auto`
+    pub fn fixed_width(&self) -> Result<Option<Pt>, DocumentGenerationError> {
+        fixed_length(&self.width)
+    }
 
-        style
+    /// The height of the node's border box, unless it's `auto`
+    pub fn fixed_height(&self) -> Result<Option<Pt>, DocumentGenerationError> {
+        fixed_length(&self.height)
     }
 }
 
-impl Style::Unmergeable {
-    pub fn merge_style(&self, rhs: &Style::Mergeable) -> Style::Unmergeable {
-        let base = Style::Mergeable::from(self.clone());
-
-        let merged: Style::Mergeable = base.merge(rhs);
+/// Relative lengths have already been converted to points, so anything other
+/// than a length in points or `auto` is malformed
+fn fixed_length(length: &str) -> Result<Option<Pt>, DocumentGenerationError> {
+    if length == "auto" {
+        return Ok(None);
+    }
 
-        merged.into()
+    match Length::try_from(length)? {
+        Length::Pt(pt) => Ok(Some(pt)),
+        _ => Err(UserInputError::MalformedUnitString {
+            source_str: length.to_owned(),
+        }
+        .into()),
     }
 }
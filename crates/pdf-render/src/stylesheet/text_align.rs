@@ -0,0 +1,22 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ts_rs::TS;
+
+/// `Start` and `End` follow the direction of the text, which is currently
+/// always left to right
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum TextAlign {
+    Start,
+    End,
+    Left,
+    Right,
+    Center,
+    Justify,
+}
+
+impl Default for TextAlign {
+    fn default() -> Self {
+        Self::Start
+    }
+}
@@ -0,0 +1,61 @@
+use optional_merge_derive::mergeable;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ts_rs::TS;
+
+use crate::values::{Color, Pt};
+
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum TextDecorationLine {
+    None,
+    Underline,
+    Overline,
+    LineThrough,
+}
+
+impl Default for TextDecorationLine {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum TextDecorationStyle {
+    Solid,
+    Dashed,
+    Double,
+}
+
+impl Default for TextDecorationStyle {
+    fn default() -> Self {
+        Self::Solid
+    }
+}
+
+#[mergeable]
+#[derive(Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[ts(export, rename_all = "camelCase")]
+pub struct TextDecoration {
+    pub line: TextDecorationLine,
+    /// Defaults to the color of the text
+    #[ts(type = "string")]
+    pub color: Option<Color>,
+    pub style: TextDecorationStyle,
+    /// Defaults to the thickness recommended by the font
+    #[ts(type = "string | number")]
+    pub thickness: Option<Pt>,
+}
+
+impl Default for TextDecoration::Unmergeable {
+    fn default() -> Self {
+        Self {
+            line: Default::default(),
+            color: None,
+            style: Default::default(),
+            thickness: None,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ts_rs::TS;
+
+/// What happens to the text past `max_lines`
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum TextOverflow {
+    /// It isn't drawn
+    Clip,
+    /// The last line ends with an ellipsis
+    Ellipsis,
+}
+
+impl Default for TextOverflow {
+    fn default() -> Self {
+        Self::Clip
+    }
+}
+
+/// How text fits within a box that's too small for it
+#[derive(TS, JsonSchema, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[ts(export)]
+pub enum TextFit {
+    /// It overflows the box
+    None,
+    /// The font size is reduced, down to `min_font_size`, until it fits
+    Shrink,
+}
+
+impl Default for TextFit {
+    fn default() -> Self {
+        Self::None
+    }
+}
@@ -1,12 +1,19 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 use ts_rs::TS;
 
 // TODO: Move to its own file
-#[derive(TS, Clone, Debug, PartialEq, Deserialize)]
+#[derive(TS, JsonSchema, Clone, Debug, PartialEq, Deserialize)]
 #[ts(export)]
 pub enum TextTransformation {
     None,
     Uppercase,
+    Lowercase,
+    /// Upper cases the first letter of every word
+    Capitalize,
+    /// Lower case letters are drawn as the font's `smcp` small capitals, or
+    /// as its full capitals scaled down when it has none
+    SmallCaps,
 }
 
 impl Default for TextTransformation {
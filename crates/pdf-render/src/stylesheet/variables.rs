@@ -0,0 +1,186 @@
+//! Substitutes `var(name)` references in a raw style with the values defined
+//!  in the stylesheet's `variables` map.
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::error::UserInputError;
+
+pub type StyleVariables = HashMap<String, Value>;
+
+fn variable_reference(value: &str) -> Option<&str> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^\s*var\(\s*(?P<name>[^()\s]+)\s*\)\s*$")
+            .expect("Regex should have been tested before production");
+    }
+
+    RE.captures(value)
+        .and_then(|capture_groups| capture_groups.name("name"))
+        .map(|name| name.as_str())
+}
+
+/// Returns a copy of `raw_style` where every string of the form
+/// `var(name)` has been replaced by the value of that variable. Variables may
+/// themselves reference other variables.
+pub fn resolve_variables(
+    raw_style: &Value,
+    variables: &StyleVariables,
+) -> Result<Value, UserInputError> {
+    resolve_value(raw_style, variables, &mut vec![])
+}
+
+fn resolve_value(
+    value: &Value,
+    variables: &StyleVariables,
+    reference_chain: &mut Vec<String>,
+) -> Result<Value, UserInputError> {
+    Ok(match value {
+        Value::String(str) => match variable_reference(str) {
+            Some(variable_name) => resolve_reference(variable_name, variables, reference_chain)?,
+            None => value.clone(),
+        },
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| resolve_value(value, variables, reference_chain))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| {
+                    Ok((
+                        key.clone(),
+                        resolve_value(value, variables, reference_chain)?,
+                    ))
+                })
+                .collect::<Result<_, UserInputError>>()?,
+        ),
+        _ => value.clone(),
+    })
+}
+
+fn resolve_reference(
+    variable_name: &str,
+    variables: &StyleVariables,
+    reference_chain: &mut Vec<String>,
+) -> Result<Value, UserInputError> {
+    if reference_chain.iter().any(|name| name == variable_name) {
+        let mut cycle = reference_chain.clone();
+        cycle.push(variable_name.to_owned());
+
+        return Err(UserInputError::CyclicStyleVariable {
+            cycle: cycle.join(" -> "),
+        });
+    }
+
+    let variable_value =
+        variables
+            .get(variable_name)
+            .ok_or_else(|| UserInputError::UndefinedStyleVariable {
+                variable_name: variable_name.to_owned(),
+            })?;
+
+    reference_chain.push(variable_name.to_owned());
+    let resolved = resolve_value(variable_value, variables, reference_chain);
+    reference_chain.pop();
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn variables(value: Value) -> StyleVariables {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn parses_references() {
+        assert_eq!(
+            variable_reference("var(brand-primary)"),
+            Some("brand-primary")
+        );
+        assert_eq!(variable_reference(" var( brand ) "), Some("brand"));
+        assert_eq!(variable_reference("brand-primary"), None);
+        assert_eq!(variable_reference("var()"), None);
+        assert_eq!(variable_reference("1px var(brand)"), None);
+    }
+
+    #[test]
+    fn resolves_nested_references() {
+        let variables = variables(json!({
+            "brand-primary": "#0080AD",
+            "body-font": "Inter",
+            "accent": "var(brand-primary)",
+            "gutter": 12
+        }));
+
+        let resolved = resolve_variables(
+            &json!({
+                "color": "var(accent)",
+                "font": { "family": "var(body-font)", "size": "14pt" },
+                "margin": { "left": "var(gutter)" }
+            }),
+            &variables,
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolved,
+            json!({
+                "color": "#0080AD",
+                "font": { "family": "Inter", "size": "14pt" },
+                "margin": { "left": 12 }
+            })
+        );
+    }
+
+    #[test]
+    fn undefined_reference() {
+        let result = resolve_variables(&json!({ "color": "var(missing)" }), &variables(json!({})));
+
+        assert!(matches!(
+            result,
+            Err(UserInputError::UndefinedStyleVariable { variable_name }) if variable_name == "missing"
+        ));
+    }
+
+    #[test]
+    fn cyclic_reference() {
+        let variables = variables(json!({
+            "a": "var(b)",
+            "b": "var(c)",
+            "c": "var(a)",
+        }));
+
+        let result = resolve_variables(&json!({ "color": "var(a)" }), &variables);
+
+        assert!(matches!(
+            result,
+            Err(UserInputError::CyclicStyleVariable { cycle }) if cycle == "a -> b -> c -> a"
+        ));
+    }
+
+    #[test]
+    fn repeated_reference_is_not_a_cycle() {
+        let variables = variables(json!({ "a": "#FFFFFF", "b": "var(a)" }));
+
+        let resolved = resolve_variables(
+            &json!({ "color": "var(b)", "backgroundColor": "var(b)" }),
+            &variables,
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolved,
+            json!({ "color": "#FFFFFF", "backgroundColor": "#FFFFFF" })
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::values::Pt;
+
+/// Superscripts are raised by this fraction of their font size
+const SUPER_SHIFT: f64 = 0.33;
+/// Subscripts are lowered by this fraction of their font size
+const SUB_SHIFT: f64 = 0.2;
+
+/// Where a span's baseline sits relative to the baseline of its line
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(from = "RawVerticalAlign")]
+pub enum VerticalAlign {
+    Baseline,
+    Super,
+    Sub,
+    /// Raised by this much, or lowered when it's negative
+    Length(Pt),
+}
+
+impl VerticalAlign {
+    /// How far a span of `font_size` is raised above the baseline
+    pub fn baseline_shift(&self, font_size: Pt) -> Pt {
+        match self {
+            Self::Baseline => Pt(0.),
+            Self::Super => Pt(font_size.0 * SUPER_SHIFT),
+            Self::Sub => Pt(-font_size.0 * SUB_SHIFT),
+            Self::Length(length) => *length,
+        }
+    }
+}
+
+impl Default for VerticalAlign {
+    fn default() -> Self {
+        Self::Baseline
+    }
+}
+
+#[derive(Deserialize)]
+enum VerticalAlignKeyword {
+    Baseline,
+    Super,
+    Sub,
+}
+
+/// The keywords are tried first, so that they aren't parsed as (invalid)
+/// lengths
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawVerticalAlign {
+    Keyword(VerticalAlignKeyword),
+    Length(Pt),
+}
+
+impl From<RawVerticalAlign> for VerticalAlign {
+    fn from(raw: RawVerticalAlign) -> Self {
+        match raw {
+            RawVerticalAlign::Keyword(VerticalAlignKeyword::Baseline) => Self::Baseline,
+            RawVerticalAlign::Keyword(VerticalAlignKeyword::Super) => Self::Super,
+            RawVerticalAlign::Keyword(VerticalAlignKeyword::Sub) => Self::Sub,
+            RawVerticalAlign::Length(length) => Self::Length(length),
+        }
+    }
+}
+
+impl JsonSchema for VerticalAlign {
+    fn schema_name() -> String {
+        String::from("VerticalAlign")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "description": "Where a span's baseline sits: a keyword, or how far it's raised (lowered when negative)",
+            "anyOf": [
+                { "type": "string", "enum": ["Baseline", "Super", "Sub"] },
+                gen.subschema_for::<Pt>()
+            ]
+        }))
+        .expect("The VerticalAlign schema is a valid JSON schema")
+    }
+}
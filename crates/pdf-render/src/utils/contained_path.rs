@@ -0,0 +1,55 @@
+//! Resolves paths that come from documents against a directory they have to
+//!  stay within, so that a document can't read any other file on the server.
+
+use std::path::{Component, Path, PathBuf};
+
+/// `path` within `directory`, or None if it's absolute, climbs out of the
+/// directory (including through symlinks) or doesn't exist. Missing files
+/// aren't told apart from the rest, so documents can't probe for files.
+pub fn resolve_contained_path(directory: &Path, path: &str) -> Option<PathBuf> {
+    let is_relative = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+
+    if !is_relative {
+        return None;
+    }
+
+    let directory = directory.canonicalize().ok()?;
+    let resolved = directory.join(path).canonicalize().ok()?;
+
+    if resolved.starts_with(&directory) {
+        Some(resolved)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_within_the_directory() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        assert_eq!(
+            resolve_contained_path(directory, "./src/lib.rs"),
+            directory.join("src/lib.rs").canonicalize().ok()
+        );
+
+        assert_eq!(resolve_contained_path(directory, "missing.json"), None);
+        assert_eq!(resolve_contained_path(directory, "../Cargo.toml"), None);
+        assert_eq!(
+            resolve_contained_path(directory, "src/../../Cargo.toml"),
+            None
+        );
+        assert_eq!(
+            resolve_contained_path(
+                directory,
+                &directory.join("Cargo.toml").display().to_string()
+            ),
+            None
+        );
+    }
+}
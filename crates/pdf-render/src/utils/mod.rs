@@ -1,6 +1,7 @@
 //! Various utils that are hard to put elsewhere and maybe should be in
 //!  other crates
 
+pub mod contained_path;
 pub mod debug_cursor;
 pub mod node_lookup;
 pub mod parent_lookup;
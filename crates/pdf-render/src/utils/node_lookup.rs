@@ -2,9 +2,10 @@ use crate::doc_structure::HasNodeId;
 use std::collections::HashMap;
 
 use crate::{
-    doc_structure::{DomNode, NodeId},
+    doc_structure::{DomNode, NodeId, TextNode},
     error::DocumentGenerationError,
-    stylesheet::{Style, Stylesheet},
+    stylesheet::{LengthContext, ResolvedStylesheet, Style},
+    values::Pt,
 };
 
 use super::parent_lookup::ParentLookup;
@@ -12,38 +13,69 @@ use super::parent_lookup::ParentLookup;
 pub struct NodeLookup<'a> {
     dom_node_lookup: HashMap<NodeId, &'a DomNode>,
     style_lookup: HashMap<NodeId, Style::Unmergeable>,
+    length_context_lookup: HashMap<NodeId, LengthContext>,
     parent_lookup: ParentLookup,
+    document_lang: Option<String>,
 }
 
 impl<'a> NodeLookup<'a> {
+    /// `page_lengths` is what the root node's lengths are resolved against,
+    /// each node's children are then resolved against that node.
     pub fn from_root_node(
         root_node: &'a DomNode,
-        stylesheet: &Stylesheet,
+        stylesheet: &ResolvedStylesheet,
+        page_lengths: &LengthContext,
+        document_lang: Option<&str>,
     ) -> Result<Self, DocumentGenerationError> {
         let mut parent_lookup = ParentLookup::new();
         let mut dom_node_lookup = HashMap::new();
         let mut partially_computed_style: HashMap<NodeId, Style::Mergeable> = HashMap::new();
         let mut style_lookup = HashMap::new();
+        let mut length_context_lookup: HashMap<NodeId, LengthContext> = HashMap::new();
 
         for (node, parent) in root_node.block_iter() {
             dom_node_lookup.insert(node.node_id(), node);
 
-            let parent_style = if let Some(parent) = parent {
-                partially_computed_style
-                    .get(&parent.node_id())
-                    .unwrap()
-                    .clone()
+            let (parent_style, lengths) = if let Some(parent) = parent {
+                (
+                    partially_computed_style
+                        .get(&parent.node_id())
+                        .unwrap()
+                        .clone(),
+                    length_context_lookup.get(&parent.node_id()).unwrap(),
+                )
             } else {
-                Default::default()
+                (Default::default(), page_lengths)
             };
 
-            let node_style = stylesheet.compute_mergeable_style(&parent_style, node.styles())?;
+            let node_style =
+                stylesheet.compute_mergeable_style(&parent_style, node.styles(), lengths)?;
+            let computed_style = Style::Unmergeable::default().merge_style(&node_style);
 
-            style_lookup.insert(
-                node.node_id(),
-                Style::Unmergeable::default().merge_style(&node_style),
-            );
+            // A fixed width is the node's border box, otherwise it stretches
+            // across its containing block within its margins
+            let border_box_width = match computed_style.fixed_width()? {
+                Some(width) => width,
+                None => lengths.containing_block_width - computed_style.margin.horizontal(),
+            };
+            let content_width = border_box_width
+                - computed_style.border.width.horizontal()
+                - computed_style.padding.horizontal();
+            let content_width = if content_width < Pt(0.) {
+                Pt(0.)
+            } else {
+                content_width
+            };
+
+            let child_lengths = if parent.is_some() {
+                lengths.for_children(computed_style.font.size, content_width)
+            } else {
+                lengths.for_root_children(computed_style.font.size, content_width)
+            };
+
+            style_lookup.insert(node.node_id(), computed_style);
             partially_computed_style.insert(node.node_id(), node_style);
+            length_context_lookup.insert(node.node_id(), child_lengths);
 
             if let Some(parent) = parent {
                 parent_lookup.add_parent(node.node_id(), parent.node_id());
@@ -54,6 +86,8 @@ impl<'a> NodeLookup<'a> {
             dom_node_lookup,
             parent_lookup,
             style_lookup,
+            length_context_lookup,
+            document_lang: document_lang.map(str::to_owned),
         })
     }
 
@@ -63,6 +97,19 @@ impl<'a> NodeLookup<'a> {
             .expect("If it has a NodeId it should exist in the lookup")
     }
 
+    /// What the lengths of anything within `node` (its children, or the spans
+    /// of a text node) are resolved against
+    pub fn get_length_context(&self, node: impl Into<NodeId>) -> &LengthContext {
+        self.length_context_lookup
+            .get(&node.into())
+            .expect("If it has a NodeId it should exist in the lookup")
+    }
+
+    /// The language of `text_node`'s text, if it or the document has one
+    pub fn get_lang<'b>(&'b self, text_node: &'b TextNode) -> Option<&'b str> {
+        text_node.lang.as_deref().or(self.document_lang.as_deref())
+    }
+
     pub fn get_dom_node(&self, node: impl Into<NodeId>) -> &DomNode {
         self.dom_node_lookup
             .get(&node.into())
@@ -89,3 +136,43 @@ impl<'a> NodeLookup<'a> {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        doc_structure::StyledNode,
+        stylesheet::Stylesheet,
+        values::{Pt, Size},
+    };
+
+    use super::*;
+
+    #[test]
+    fn resolves_percentages_against_a_fixed_width() {
+        let stylesheet = serde_json::from_value::<Stylesheet>(json!({
+            "sidebar": { "width": "200pt", "padding": { "left": "20pt" } },
+            "half": { "width": "50%" },
+        }))
+        .unwrap()
+        .resolve()
+        .unwrap();
+
+        let half = DomNode::Styled(StyledNode::with_children(vec![], &["half"]));
+        let half_id = half.node_id();
+        let sidebar = DomNode::Styled(StyledNode::with_children(vec![half], &["sidebar"]));
+        let root = DomNode::Styled(StyledNode::with_children(vec![sidebar], &[]));
+
+        let node_lookup = NodeLookup::from_root_node(
+            &root,
+            &stylesheet,
+            &LengthContext::new(Size::from((Pt(500.), Pt(700.))), 72.),
+            None,
+        )
+        .unwrap();
+
+        // Half of the sidebar's content width, not of the page's
+        assert_eq!(node_lookup.get_style(half_id).width, "90pt");
+    }
+}
@@ -1,4 +1,6 @@
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::Deserialize;
+use serde_json::json;
 use ts_rs::TS;
 
 use crate::error::UserInputError;
@@ -6,44 +8,104 @@ use crate::error::UserInputError;
 #[derive(TS, Deserialize, Debug, Clone, PartialEq)]
 #[serde(try_from = "&str")]
 pub struct Color {
-    pub r: f64,
-    pub g: f64,
-    pub b: f64,
+    pub components: ColorComponents,
     pub a: f64,
 }
 
+#[derive(TS, Debug, Clone, PartialEq)]
+pub enum ColorComponents {
+    Rgb {
+        r: f64,
+        g: f64,
+        b: f64,
+    },
+    Cmyk(Cmyk),
+    /// A named ink, which is printed on a plate of its own
+    Spot(SpotColor),
+}
+
+#[derive(TS, Debug, Clone, PartialEq)]
+pub struct Cmyk {
+    pub c: f64,
+    pub m: f64,
+    pub y: f64,
+    pub k: f64,
+}
+
+#[derive(TS, Debug, Clone, PartialEq)]
+pub struct SpotColor {
+    pub name: String,
+    /// How much of the ink is laid down, from 0 to 1
+    pub tint: f64,
+    /// What the ink looks like at full strength, for devices that don't have
+    /// it
+    pub alternate: Cmyk,
+}
+
 impl Color {
     pub fn white() -> Self {
-        Self {
-            r: 1.,
-            g: 1.,
-            b: 1.,
-            a: 1.,
-        }
+        Self::rgb(1., 1., 1.)
     }
 
     pub fn black() -> Self {
+        Self::rgb(0., 0., 0.)
+    }
+
+    pub fn rgb(r: f64, g: f64, b: f64) -> Self {
         Self {
-            r: 0.,
-            g: 0.,
-            b: 0.,
+            components: ColorComponents::Rgb { r, g, b },
             a: 1.,
         }
     }
 }
 
+impl JsonSchema for Color {
+    fn schema_name() -> String {
+        String::from("Color")
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "description": "Any CSS color, e.g. `#ff0000`, `rgb(255, 0, 0)` or `red`, \
+                a process color, e.g. `cmyk(0%, 100%, 100%, 0%)`, or a spot color \
+                with the process color it stands in for, e.g. \
+                `spot(\"PANTONE 186 C\", cmyk(0%, 100%, 81%, 4%), 100%)`",
+            "type": "string"
+        }))
+        .expect("The Color schema is a valid JSON schema")
+    }
+}
+
 impl Default for Color {
     fn default() -> Self {
         Self::black()
     }
 }
 
+/// Spot colors need a color space of their own, which the writer sets up, so
+/// this gives their alternate
 impl From<Color> for printpdf::Color {
     fn from(color: Color) -> Self {
-        Self::Rgb(printpdf::Rgb {
-            r: color.r,
-            g: color.g,
-            b: color.b,
+        match color.components {
+            ColorComponents::Rgb { r, g, b } => Self::Rgb(printpdf::Rgb {
+                r,
+                g,
+                b,
+                icc_profile: None,
+            }),
+            ColorComponents::Cmyk(cmyk) => cmyk.into(),
+            ColorComponents::Spot(spot) => spot.alternate.into(),
+        }
+    }
+}
+
+impl From<Cmyk> for printpdf::Color {
+    fn from(cmyk: Cmyk) -> Self {
+        Self::Cmyk(printpdf::Cmyk {
+            c: cmyk.c,
+            m: cmyk.m,
+            y: cmyk.y,
+            k: cmyk.k,
             icc_profile: None,
         })
     }
@@ -53,8 +115,164 @@ impl TryFrom<&str> for Color {
     type Error = UserInputError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+
+        let malformed = |reason: &str| UserInputError::MalformedColor {
+            color: value.to_owned(),
+            reason: reason.to_owned(),
+        };
+
+        if let Some(arguments) = function_arguments(value, "spot") {
+            return parse_spot(arguments).ok_or_else(|| {
+                malformed("expected spot(\"<name>\", cmyk(<c>, <m>, <y>, <k>), <tint>)")
+            });
+        }
+
+        if let Some(arguments) =
+            function_arguments(value, "cmyk").or_else(|| function_arguments(value, "device-cmyk"))
+        {
+            return parse_cmyk(arguments)
+                .ok_or_else(|| malformed("expected cmyk(<c>, <m>, <y>, <k>) or a fifth alpha"));
+        }
+
         let (r, g, b, a) = color_processing::Color::new_string(value)?.get_rgba();
 
-        Ok(Color { r, g, b, a })
+        Ok(Color {
+            a,
+            ..Color::rgb(r, g, b)
+        })
+    }
+}
+
+/// What's between the parentheses of `value`, if it's a call of `function`
+pub(super) fn function_arguments<'a>(value: &'a str, function: &str) -> Option<&'a str> {
+    let (name, rest) = value.split_at(value.find('(')?);
+
+    if !name.trim_end().eq_ignore_ascii_case(function) {
+        return None;
+    }
+
+    rest.strip_prefix('(')?.strip_suffix(')')
+}
+
+/// A number from 0 to 1, or a percentage
+fn parse_fraction(value: &str) -> Option<f64> {
+    let fraction = match value.trim().strip_suffix('%') {
+        Some(percentage) => percentage.trim_end().parse::<f64>().ok()? / 100.,
+        None => value.trim().parse().ok()?,
+    };
+
+    if (0. ..=1.).contains(&fraction) {
+        Some(fraction)
+    } else {
+        None
+    }
+}
+
+/// `<c> <m> <y> <k>`, separated by commas or spaces and optionally followed by
+/// an alpha, as in CSS's `device-cmyk()`
+fn parse_cmyk(arguments: &str) -> Option<Color> {
+    let components = arguments
+        .split(|char: char| char == ',' || char == '/' || char.is_whitespace())
+        .filter(|component| !component.is_empty())
+        .map(parse_fraction)
+        .collect::<Option<Vec<_>>>()?;
+
+    match components[..] {
+        [c, m, y, k] => Some(Color {
+            components: ColorComponents::Cmyk(Cmyk { c, m, y, k }),
+            a: 1.,
+        }),
+        [c, m, y, k, a] => Some(Color {
+            components: ColorComponents::Cmyk(Cmyk { c, m, y, k }),
+            a,
+        }),
+        _ => None,
+    }
+}
+
+/// `"<name>", cmyk(...)` followed by an optional tint
+fn parse_spot(arguments: &str) -> Option<Color> {
+    let arguments = arguments.trim_start();
+    let quote = arguments
+        .chars()
+        .next()
+        .filter(|&c| c == '"' || c == '\'')?;
+
+    let (name, rest) = arguments[1..].split_at(arguments[1..].find(quote)?);
+    let rest = rest[1..].trim_start().strip_prefix(',')?;
+
+    let (alternate, tint) = match rest.find(')') {
+        Some(end) => rest.split_at(end + 1),
+        None => return None,
+    };
+
+    // The alternate stands in for the ink, which is opaque
+    let alternate = match parse_cmyk(function_arguments(alternate.trim(), "cmyk")?)? {
+        Color {
+            components: ColorComponents::Cmyk(alternate),
+            a,
+        } if a >= 1. => alternate,
+        _ => return None,
+    };
+
+    let tint = match tint.trim() {
+        "" => 1.,
+        tint => parse_fraction(tint.strip_prefix(',')?)?,
+    };
+
+    (!name.is_empty()).then(|| Color {
+        components: ColorComponents::Spot(SpotColor {
+            name: name.to_owned(),
+            tint,
+            alternate,
+        }),
+        a: 1.,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_process_and_spot_colors() {
+        assert_eq!(
+            Color::try_from("cmyk(0%, 100%, 50%, 0.2)").unwrap(),
+            Color {
+                components: ColorComponents::Cmyk(Cmyk {
+                    c: 0.,
+                    m: 1.,
+                    y: 0.5,
+                    k: 0.2
+                }),
+                a: 1.
+            }
+        );
+        assert_eq!(
+            Color::try_from("device-cmyk(0 0 0 100% / 50%)").unwrap().a,
+            0.5
+        );
+        assert_eq!(
+            Color::try_from("spot(\"PANTONE 186 C\", cmyk(0%, 100%, 81%, 4%), 40%)").unwrap(),
+            Color {
+                components: ColorComponents::Spot(SpotColor {
+                    name: String::from("PANTONE 186 C"),
+                    tint: 0.4,
+                    alternate: Cmyk {
+                        c: 0.,
+                        m: 1.,
+                        y: 0.81,
+                        k: 0.04
+                    }
+                }),
+                a: 1.
+            }
+        );
+        assert_eq!(Color::try_from("#ffffff").unwrap(), Color::white());
+
+        assert!(Color::try_from("cmyk(0, 0, 0)").is_err());
+        assert!(Color::try_from("cmyk(0, 0, 0, 150%)").is_err());
+        assert!(Color::try_from("spot(Gold, cmyk(0, 0, 0, 1))").is_err());
     }
 }
@@ -4,13 +4,15 @@ use std::{
 };
 
 use regex::Regex;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::Deserialize;
+use serde_json::json;
 
 use lazy_static::lazy_static;
 
 use crate::error::{DocumentGenerationError, UserInputError};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct Size<T> {
     pub width: T,
     pub height: T,
@@ -77,18 +79,78 @@ impl<'de> Deserialize<'de> for Pt {
     }
 }
 
+impl JsonSchema for Pt {
+    fn schema_name() -> String {
+        String::from("Pt")
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "description": "A number of points, or a number followed by one of the supported units (defaults to px)",
+            "oneOf": [
+                { "type": "number" },
+                {
+                    "type": "string",
+                    "pattern": r"^-?[.\d]+(px|mm|cm|pt|in|pc|em|rem|%|vw|vh|PX|MM|CM|PT|IN|PC|EM|REM|VW|VH)?$"
+                }
+            ]
+        }))
+        .expect("The Pt schema is a valid JSON schema")
+    }
+}
+
 impl Display for Pt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} pt.", self.0)
     }
 }
 
+impl Pt {
+    pub fn from_px(px: f64, dpi: f64) -> Self {
+        Self(px * 72. / dpi)
+    }
+}
+
+/// `px` lengths outside of styles (i.e. page margins) are always converted at
+/// this resolution
+pub const DEFAULT_DPI: f64 = 300.;
+
 impl TryFrom<&str> for Pt {
     type Error = DocumentGenerationError;
 
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match Length::try_from(value)? {
+            Length::Pt(pt) => Ok(pt),
+            Length::Px(px) => Ok(Pt::from_px(px, DEFAULT_DPI)),
+            _ => Err(UserInputError::ContextDependentLength {
+                length: String::from(value),
+            }
+            .into()),
+        }
+    }
+}
+
+/// A length as it's written in a document. Everything but `Pt` depends on
+/// where the length is used, see `stylesheet::LengthContext`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Length {
+    Pt(Pt),
+    Px(f64),
+    Em(f64),
+    Rem(f64),
+    Percent(f64),
+    /// A percentage of the page's content width
+    Vw(f64),
+    /// A percentage of the page's content height
+    Vh(f64),
+}
+
+impl TryFrom<&str> for Length {
+    type Error = DocumentGenerationError;
+
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"^(?i)(?P<quantity>[\.\d]+)(?P<units>\D+)?$")
+            static ref RE: Regex = Regex::new(r"^(?i)(?P<quantity>-?[\.\d]+)(?P<units>\D+)?$")
                 .expect("Regex should have been tested before production");
         }
 
@@ -108,12 +170,17 @@ impl TryFrom<&str> for Pt {
         let units = capture_groups.name("units").map_or("px", |u| u.as_str());
 
         Ok(match units.to_lowercase().as_str() {
-            "px" => Mm(quantity * (25.4 / 300.)).into(),
-            "mm" => Mm(quantity).into(),
-            "cm" => Mm(quantity * 10.0).into(),
-            "pt" => Pt(quantity),
-            "in" => Pt(quantity * 72.),
-            "pc" => Pt(quantity * 6.),
+            "px" => Length::Px(quantity),
+            "mm" => Length::Pt(Mm(quantity).into()),
+            "cm" => Length::Pt(Mm(quantity * 10.0).into()),
+            "pt" => Length::Pt(Pt(quantity)),
+            "in" => Length::Pt(Pt(quantity * 72.)),
+            "pc" => Length::Pt(Pt(quantity * 6.)),
+            "em" => Length::Em(quantity),
+            "rem" => Length::Rem(quantity),
+            "%" => Length::Percent(quantity),
+            "vw" => Length::Vw(quantity),
+            "vh" => Length::Vh(quantity),
             unit => {
                 return Err(UserInputError::UnsupportedUnit {
                     attached_unit: String::from(unit),
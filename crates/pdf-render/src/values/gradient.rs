@@ -0,0 +1,420 @@
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::error::UserInputError;
+
+use super::{color::function_arguments, Color, ColorComponents};
+
+/// A CSS `linear-gradient(...)` or `radial-gradient(...)`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "&str")]
+pub enum Gradient {
+    Linear {
+        direction: GradientDirection,
+        stops: Vec<ColorStop>,
+    },
+    Radial {
+        shape: RadialShape,
+        /// The center, as fractions of the box's width and height from its
+        /// top left corner
+        center: (f64, f64),
+        stops: Vec<ColorStop>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientDirection {
+    /// Clockwise from pointing up, in degrees
+    Angle(f64),
+    /// Towards a corner, so the corners either side of it share a color
+    Corner { right: bool, top: bool },
+}
+
+/// Radial gradients end at the corner farthest from their center
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RadialShape {
+    Circle,
+    /// With the proportions of the box
+    Ellipse,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorStop {
+    pub color: Color,
+    /// From 0 at the start of the gradient to 1 at its end, or spaced evenly
+    /// between the stops either side if it's not given
+    pub position: Option<f64>,
+}
+
+impl Gradient {
+    pub fn stops(&self) -> &[ColorStop] {
+        match self {
+            Self::Linear { stops, .. } | Self::Radial { stops, .. } => stops,
+        }
+    }
+
+    /// The position of every stop, which never goes back past the one before
+    /// it, with stops at the very start and end if they're missing
+    pub fn resolved_stops(&self) -> Vec<(f64, &Color)> {
+        let stops = self.stops();
+        let mut positions: Vec<Option<f64>> = stops.iter().map(|stop| stop.position).collect();
+
+        if let Some(first) = positions.first_mut() {
+            first.get_or_insert(0.);
+        }
+        if let Some(last) = positions.last_mut() {
+            last.get_or_insert(1.);
+        }
+
+        let mut resolved = Vec::with_capacity(stops.len() + 2);
+        let mut previous = 0.;
+        let mut index = 0;
+
+        while index < positions.len() {
+            let position = match positions[index] {
+                Some(position) => position.max(previous),
+                None => {
+                    // Spread the stops without positions between the ones
+                    // around them
+                    let next_index = (index..positions.len())
+                        .find(|&next| positions[next].is_some())
+                        .expect("The last stop has a position");
+                    let next = positions[next_index]
+                        .expect("The found stop has a position")
+                        .max(previous);
+                    let step = (next - previous) / (next_index - index + 1) as f64;
+
+                    previous + step
+                }
+            };
+
+            positions[index] = Some(position);
+            resolved.push((position, &stops[index].color));
+            previous = position;
+            index += 1;
+        }
+
+        if let Some(&(first_position, first_color)) = resolved.first() {
+            if first_position > 0. {
+                resolved.insert(0, (0., first_color));
+            }
+        }
+        if let Some(&(last_position, last_color)) = resolved.last() {
+            if last_position < 1. {
+                resolved.push((1., last_color));
+            }
+        }
+
+        resolved
+    }
+}
+
+impl JsonSchema for Gradient {
+    fn schema_name() -> String {
+        String::from("Gradient")
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "description": "A CSS gradient, e.g. `linear-gradient(to right, #ff0000, #0000ff 80%)` \
+                or `radial-gradient(circle at 25% 50%, white, cmyk(0%, 0%, 0%, 100%))`. \
+                The stops must be opaque, and either all RGB or all CMYK",
+            "type": "string"
+        }))
+        .expect("The Gradient schema is a valid JSON schema")
+    }
+}
+
+impl TryFrom<&str> for Gradient {
+    type Error = UserInputError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+
+        let malformed = |reason: &str| UserInputError::MalformedGradient {
+            gradient: value.to_owned(),
+            reason: reason.to_owned(),
+        };
+
+        let (is_linear, arguments) = match (
+            function_arguments(value, "linear-gradient"),
+            function_arguments(value, "radial-gradient"),
+        ) {
+            (Some(arguments), _) => (true, arguments),
+            (_, Some(arguments)) => (false, arguments),
+            _ => {
+                return Err(malformed(
+                    "expected linear-gradient(...) or radial-gradient(...)",
+                ))
+            }
+        };
+
+        let mut arguments = split_arguments(arguments);
+
+        let gradient = if is_linear {
+            let direction = match arguments.first().and_then(|first| parse_direction(first)) {
+                Some(direction) => {
+                    arguments.remove(0);
+                    direction
+                }
+                None => GradientDirection::Angle(180.),
+            };
+
+            Gradient::Linear {
+                direction,
+                stops: parse_stops(&arguments)?,
+            }
+        } else {
+            let (shape, center) = match arguments.first().and_then(|first| parse_shape(first)) {
+                Some(Ok(shape_and_center)) => {
+                    arguments.remove(0);
+                    shape_and_center
+                }
+                Some(Err(reason)) => return Err(malformed(reason)),
+                None => (RadialShape::Ellipse, (0.5, 0.5)),
+            };
+
+            Gradient::Radial {
+                shape,
+                center,
+                stops: parse_stops(&arguments)?,
+            }
+        };
+
+        let stops = gradient.stops();
+
+        if stops.len() < 2 {
+            return Err(malformed("expected at least two color stops"));
+        }
+
+        if stops.iter().any(|stop| stop.color.a < 1.) {
+            return Err(malformed("color stops must be opaque"));
+        }
+
+        let is_rgb =
+            |stop: &ColorStop| matches!(stop.color.components, ColorComponents::Rgb { .. });
+        let is_cmyk = |stop: &ColorStop| matches!(stop.color.components, ColorComponents::Cmyk(_));
+
+        if !stops.iter().all(is_rgb) && !stops.iter().all(is_cmyk) {
+            return Err(malformed("color stops must all be RGB or all be CMYK"));
+        }
+
+        Ok(gradient)
+    }
+}
+
+/// Splits `arguments` at the commas that aren't within parentheses or quotes
+fn split_arguments(arguments: &str) -> Vec<&str> {
+    let mut split = vec![];
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+
+    for (index, char) in arguments.char_indices() {
+        match (char, quote) {
+            ('"' | '\'', None) => quote = Some(char),
+            (_, Some(open)) if char == open => quote = None,
+            (_, Some(_)) => {}
+            ('(', None) => depth += 1,
+            (')', None) => depth -= 1,
+            (',', None) if depth == 0 => {
+                split.push(arguments[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    split.push(arguments[start..].trim());
+
+    split
+}
+
+fn parse_direction(argument: &str) -> Option<GradientDirection> {
+    if let Some(sides) = argument.strip_prefix("to ") {
+        let mut horizontal = None;
+        let mut vertical = None;
+
+        for side in sides.split_whitespace() {
+            match side {
+                "left" => horizontal = Some(false),
+                "right" => horizontal = Some(true),
+                "top" => vertical = Some(true),
+                "bottom" => vertical = Some(false),
+                _ => return None,
+            }
+        }
+
+        return match (horizontal, vertical) {
+            (Some(right), Some(top)) => Some(GradientDirection::Corner { right, top }),
+            (Some(right), None) => Some(GradientDirection::Angle(if right { 90. } else { 270. })),
+            (None, Some(top)) => Some(GradientDirection::Angle(if top { 0. } else { 180. })),
+            (None, None) => None,
+        };
+    }
+
+    let angle = if let Some(degrees) = argument.strip_suffix("deg") {
+        degrees.trim().parse().ok()?
+    } else if let Some(turns) = argument.strip_suffix("turn") {
+        turns.trim().parse::<f64>().ok()? * 360.
+    } else {
+        return None;
+    };
+
+    Some(GradientDirection::Angle(angle))
+}
+
+type ShapeAndCenter = (RadialShape, (f64, f64));
+
+/// `[circle | ellipse] [at <x> <y>]`, where the position is given by
+/// percentages or keywords, or None if `argument` is a color stop
+fn parse_shape(argument: &str) -> Option<Result<ShapeAndCenter, &'static str>> {
+    let mut words = argument.split_whitespace().peekable();
+
+    let shape = match words.peek() {
+        Some(&"circle") => RadialShape::Circle,
+        Some(&"ellipse") | Some(&"at") => RadialShape::Ellipse,
+        _ => return None,
+    };
+
+    if words.peek() != Some(&"at") {
+        words.next();
+    }
+
+    let position: Vec<&str> = match words.next() {
+        None => vec![],
+        Some("at") => words.collect(),
+        Some(_) => return Some(Err("expected the shape to be followed by `at <x> <y>`")),
+    };
+
+    let fraction = |word: &str, is_vertical: bool| match (word, is_vertical) {
+        ("center", _) => Some(0.5),
+        ("left", false) | ("top", true) => Some(0.),
+        ("right", false) | ("bottom", true) => Some(1.),
+        _ => word
+            .strip_suffix('%')
+            .and_then(|percentage| percentage.parse::<f64>().ok())
+            .map(|percentage| percentage / 100.),
+    };
+
+    let center = match position[..] {
+        [] => Some((0.5, 0.5)),
+        [vertical @ ("top" | "bottom")] => fraction(vertical, true).map(|y| (0.5, y)),
+        [horizontal] => fraction(horizontal, false).map(|x| (x, 0.5)),
+        [vertical @ ("top" | "bottom"), horizontal] | [horizontal, vertical] => {
+            fraction(horizontal, false).zip(fraction(vertical, true))
+        }
+        _ => None,
+    };
+
+    Some(
+        center
+            .map(|center| (shape, center))
+            .ok_or("expected the position to be percentages or keywords"),
+    )
+}
+
+/// `<color> [<position>%]`
+fn parse_stops(arguments: &[&str]) -> Result<Vec<ColorStop>, UserInputError> {
+    arguments
+        .iter()
+        .map(|argument| {
+            let position =
+                argument
+                    .rsplit_once(char::is_whitespace)
+                    .and_then(|(color, position)| {
+                        let percentage = position.strip_suffix('%')?.parse::<f64>().ok()?;
+
+                        Some((color, (percentage / 100.).clamp(0., 1.)))
+                    });
+
+            Ok(match position {
+                Some((color, position)) => ColorStop {
+                    color: Color::try_from(color)?,
+                    position: Some(position),
+                },
+                None => ColorStop {
+                    color: Color::try_from(*argument)?,
+                    position: None,
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gradients() {
+        let gradient =
+            Gradient::try_from("linear-gradient(to top right, rgb(255, 0, 0), #00ff00 40%, blue)")
+                .unwrap();
+
+        assert_eq!(
+            gradient,
+            Gradient::Linear {
+                direction: GradientDirection::Corner {
+                    right: true,
+                    top: true
+                },
+                stops: vec![
+                    ColorStop {
+                        color: Color::rgb(1., 0., 0.),
+                        position: None
+                    },
+                    ColorStop {
+                        color: Color::rgb(0., 1., 0.),
+                        position: Some(0.4)
+                    },
+                    ColorStop {
+                        color: Color::rgb(0., 0., 1.),
+                        position: None
+                    },
+                ]
+            }
+        );
+
+        let gradient = Gradient::try_from(
+            "radial-gradient(circle at left 25%, cmyk(0, 0, 0, 0), cmyk(0, 0, 0, 1) 50%)",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            gradient,
+            Gradient::Radial {
+                shape: RadialShape::Circle,
+                center: (x, y),
+                ..
+            } if x == 0. && y == 0.25
+        ));
+        assert_eq!(
+            gradient
+                .resolved_stops()
+                .iter()
+                .map(|(position, _)| *position)
+                .collect::<Vec<_>>(),
+            vec![0., 0.5, 1.]
+        );
+
+        assert!(Gradient::try_from("linear-gradient(90deg, red)").is_err());
+        assert!(Gradient::try_from("linear-gradient(red, cmyk(0, 0, 0, 1))").is_err());
+    }
+
+    #[test]
+    fn spreads_stops_without_positions() {
+        let gradient =
+            Gradient::try_from("linear-gradient(red 20%, lime, blue, white 80%)").unwrap();
+
+        assert_eq!(
+            gradient
+                .resolved_stops()
+                .iter()
+                .map(|(position, _)| (position * 100.).round())
+                .collect::<Vec<_>>(),
+            vec![0., 20., 40., 60., 80., 100.]
+        );
+    }
+}
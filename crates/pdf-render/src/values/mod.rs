@@ -4,6 +4,8 @@
 mod color;
 mod conversions;
 mod geometry;
+mod gradient;
 
 pub use color::*;
 pub use geometry::*;
+pub use gradient::*;
@@ -1,22 +1,62 @@
 use clap::Parser;
-use std::fs::{self, File};
+use std::{
+    fs::{self, File},
+    path::Path,
+};
 
-use pdf_render::{build_pdf_from_dom, doc_structure::DocStructure};
+use pdf_render::{
+    build_pdf_from_dom,
+    doc_structure::{document_schema, DocStructure},
+    stylesheet::StylesheetRegistry,
+};
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
     #[clap(default_value_t = String::from("./assets/example.json"))]
     file_path: String,
+
+    /// Directory of `*.json` stylesheets that documents can import by name
+    #[clap(long)]
+    stylesheet_dir: Option<String>,
+
+    /// Directory that output intents' ICC profiles are read from
+    #[clap(long, default_value_t = String::from("."))]
+    output_profile_dir: String,
+
+    /// Print the JSON Schema for documents instead of rendering one
+    #[clap(long)]
+    schema: bool,
 }
 
 pub fn main() {
     let args = Args::parse();
 
+    if args.schema {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document_schema()).unwrap()
+        );
+        return;
+    }
+
     let example_json = fs::read_to_string(&args.file_path).unwrap();
 
     let pdf_dom: DocStructure = serde_json::from_str(&example_json).unwrap();
 
+    let mut stylesheet_registry = StylesheetRegistry::new();
+    stylesheet_registry.allow_file_imports(".");
+
+    if let Some(stylesheet_dir) = &args.stylesheet_dir {
+        stylesheet_registry.load_directory(stylesheet_dir).unwrap();
+    }
+
     let mut file_to_write = File::create("output.pdf").unwrap();
-    build_pdf_from_dom(&pdf_dom, &mut file_to_write).unwrap();
+    build_pdf_from_dom(
+        &pdf_dom,
+        &stylesheet_registry,
+        Some(Path::new(&args.output_profile_dir)),
+        &mut file_to_write,
+    )
+    .unwrap();
 }
@@ -2,21 +2,36 @@ use actix_web::{dev::Service, get, post, web, App, HttpResponse, HttpServer, mid
 
 use logger::LogData;
 use logzio::{LogzIoSender, LogzIoSenderBuilder};
-use pdf_render::{build_pdf_from_dom, doc_structure::DocStructure, error::DocumentGenerationError};
+use pdf_render::{
+    build_pdf_from_dom,
+    doc_structure::{document_schema, DocStructure},
+    error::DocumentGenerationError,
+    stylesheet::StylesheetRegistry,
+};
 
 use rollbar::{self, report_panics};
+use std::path::PathBuf;
 use tracing::{error, info, info_span, Instrument};
 use tracing_subscriber::{filter, prelude::*, EnvFilter};
 
 mod logger;
 
-struct AppState {}
+struct AppState {
+    stylesheet_registry: StylesheetRegistry,
+    /// Where output intents' ICC profiles are read from
+    output_profile_dir: Option<PathBuf>,
+}
 
-fn pdf_response_from_dom(pdf_dom: DocStructure, _app_state: &AppState) -> HttpResponse {
+fn pdf_response_from_dom(pdf_dom: DocStructure, app_state: &AppState) -> HttpResponse {
     let _span = info_span!("Beginning to parse PDF struct").entered();
 
     let filename = pdf_dom.filename.clone();
-    let response = build_pdf_from_dom(&pdf_dom, Vec::new());
+    let response = build_pdf_from_dom(
+        &pdf_dom,
+        &app_state.stylesheet_registry,
+        app_state.output_profile_dir.as_deref(),
+        Vec::new(),
+    );
 
     info!("An event!");
 
@@ -46,6 +61,11 @@ async fn render_pdf(body: web::Json<DocStructure>, data: web::Data<AppState>) ->
     pdf_response_from_dom(pdf_dom, &data)
 }
 
+#[get("/schema")]
+async fn render_schema() -> HttpResponse {
+    HttpResponse::Ok().json(document_schema())
+}
+
 async fn heartbeat() -> HttpResponse {
     let _span = info_span!("Beginning return heartbeat").entered();
     info!("Heartbeat!");
@@ -119,7 +139,27 @@ async fn main() -> Result<(), std::io::Error> {
         std::env::var("PORT").map_or(DEFAULT_PORT, |str| str.parse().unwrap_or(DEFAULT_PORT));
 
     let base_path = std::env::var("BASE_PATH").unwrap_or_else(|_| "/".to_owned());
-    
+
+    // Documents come from clients, so they can only import the registered
+    // stylesheets rather than reading files from disk
+    let mut stylesheet_registry = StylesheetRegistry::new();
+
+    if let Ok(stylesheet_dir) = std::env::var("STYLESHEET_DIR") {
+        info!("Loading shared stylesheets from: {stylesheet_dir}");
+
+        stylesheet_registry
+            .load_directory(&stylesheet_dir)
+            .expect("Shared stylesheets should be valid");
+    }
+
+    let output_profile_dir = std::env::var("OUTPUT_PROFILE_DIR").ok().map(PathBuf::from);
+
+    // Loaded once and shared between all of the workers
+    let app_state = web::Data::new(AppState {
+        stylesheet_registry,
+        output_profile_dir,
+    });
+
     info!("Starting server w/ port: {port} and base-path: {base_path}");
 
     HttpServer::new(move || {
@@ -143,10 +183,11 @@ async fn main() -> Result<(), std::io::Error> {
                     Ok(res)
                 }
             })
-            .app_data(web::Data::new(AppState {}))
+            .app_data(app_state.clone())
             .service(
                 web::scope(&base_path)
                     .service(render_pdf)
+                    .service(render_schema)
                     .route("/health-check", web::get().to(heartbeat)),
             );
 